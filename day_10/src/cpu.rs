@@ -0,0 +1,275 @@
+use std::{collections::HashMap, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Registers {
+    pub x: i64,
+    pub y: i64,
+    // Index of the next instruction to run
+    pub pc: i64,
+}
+
+// Applied once the last cycle of an instruction is over, `pc` already points past it.
+// Returns the new registers, None if a register overflows.
+pub type Effect = fn(Registers, &[i64]) -> Option<Registers>;
+
+// Entry of the instruction set table
+#[derive(Clone, Copy)]
+pub struct InstructionSpec {
+    pub name: &'static str,
+    pub operands: usize,
+    pub cycles: usize,
+    pub effect: Effect,
+}
+
+#[derive(Clone)]
+pub struct Instruction {
+    pub spec: InstructionSpec,
+    pub operands: Vec<i64>,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.spec.name)?;
+        for operand in &self.operands {
+            write!(f, " {}", operand)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    // Lines are 1-based
+    UnknownInstruction { line: usize, name: String },
+    InvalidOperand { line: usize, operand: String },
+    OperandCount { line: usize, expected: usize, found: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnknownInstruction { line, name } =>
+                write!(f, "Unknown instruction '{}' at line {}", name, line),
+            ParseError::InvalidOperand { line, operand } =>
+                write!(f, "Invalid operand '{}' at line {}", operand, line),
+            ParseError::OperandCount { line, expected, found } =>
+                write!(f, "Line {} has {} operands, expected {}", line, found, expected),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct InstructionSet {
+    specs: HashMap<&'static str, InstructionSpec>,
+}
+
+impl Default for InstructionSet {
+    // Instructions from the puzzle statement
+    fn default() -> InstructionSet {
+        let mut set = InstructionSet { specs: HashMap::new() };
+        set.register(InstructionSpec { name: "noop", operands: 0, cycles: 1, effect: |r, _| Some(r) });
+        set.register(InstructionSpec { name: "addx", operands: 1, cycles: 2, effect: |r, o| Some(Registers { x: r.x.checked_add(o[0])?, ..r }) });
+        set
+    }
+}
+
+impl InstructionSet {
+    pub fn extended() -> InstructionSet {
+        let mut set = InstructionSet::default();
+        set.register(InstructionSpec { name: "addy", operands: 1, cycles: 2, effect: |r, o| Some(Registers { y: r.y.checked_add(o[0])?, ..r }) });
+        set.register(InstructionSpec { name: "mulx", operands: 1, cycles: 3, effect: |r, o| Some(Registers { x: r.x.checked_mul(o[0])?, ..r }) });
+        // Relative to the jump itself, so `jmp 0` loops until the cycle budget runs out
+        set.register(InstructionSpec { name: "jmp", operands: 1, cycles: 1, effect: |r, o| Some(Registers { pc: r.pc.checked_add(o[0])?.checked_sub(1)?, ..r }) });
+        set
+    }
+
+    // Adds or replaces an instruction
+    pub fn register(&mut self, spec: InstructionSpec) {
+        assert!(spec.cycles > 0, "Instructions take at least one cycle");
+        self.specs.insert(spec.name, spec);
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Vec<Instruction>, ParseError> {
+        let mut program = vec![];
+        for (i, text) in input.lines().enumerate() {
+            let line = i + 1;
+            let mut parts = text.split_whitespace();
+            let Some(name) = parts.next() else {
+                continue;
+            };
+            let spec = *self.specs.get(name)
+                .ok_or(ParseError::UnknownInstruction { line, name: name.to_string() })?;
+            let operands = parts
+                .map(|part| part.parse::<i64>().map_err(|_| ParseError::InvalidOperand { line, operand: part.to_string() }))
+                .collect::<Result<Vec<i64>, ParseError>>()?;
+            if operands.len() != spec.operands {
+                return Err(ParseError::OperandCount { line, expected: spec.operands, found: operands.len() });
+            }
+            program.push(Instruction { spec, operands });
+        }
+        Ok(program)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CpuError {
+    // The program was still running after this many cycles
+    CycleLimit(usize),
+    Overflow { cycle: usize, instruction: String },
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CpuError::CycleLimit(cycles) => write!(f, "Program still running after {} cycles", cycles),
+            CpuError::Overflow { cycle, instruction } => write!(f, "Overflow in '{}' on cycle {}", instruction, cycle),
+        }
+    }
+}
+
+// Default cycle budget, far more than any puzzle input needs
+pub const MAX_CYCLES: usize = 1_000_000;
+
+// State during a cycle, before the instruction finishing on it takes effect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tick {
+    pub cycle: usize,
+    pub registers: Registers,
+}
+
+pub struct Cpu {
+    pub registers: Registers,
+    // Cycle about to run, starting at 1
+    pub cycle: usize,
+    // Budget after which `tick` fails, so looping programs stop
+    pub max_cycles: usize,
+    program: Vec<Instruction>,
+    // Cycles already spent on the current instruction
+    elapsed: usize,
+}
+
+impl Cpu {
+    pub fn new(program: Vec<Instruction>) -> Cpu {
+        Cpu {
+            registers: Registers { x: 1, ..Registers::default() },
+            cycle: 1,
+            max_cycles: MAX_CYCLES,
+            program,
+            elapsed: 0,
+        }
+    }
+
+    // Stopped once `pc` leaves the program
+    pub fn is_halted(&self) -> bool {
+        self.registers.pc < 0 || self.registers.pc as usize >= self.program.len()
+    }
+
+    // Runs one cycle, None once halted. Timing and effects all come from the
+    // instruction table, so new instructions need no change here.
+    pub fn tick(&mut self) -> Result<Option<Tick>, CpuError> {
+        if self.is_halted() {
+            return Ok(None);
+        }
+        if self.cycle > self.max_cycles {
+            return Err(CpuError::CycleLimit(self.max_cycles));
+        }
+        let tick = Tick { cycle: self.cycle, registers: self.registers };
+        let instruction = &self.program[self.registers.pc as usize];
+        self.elapsed += 1;
+        if self.elapsed == instruction.spec.cycles {
+            self.elapsed = 0;
+            self.registers.pc += 1;
+            self.registers = (instruction.spec.effect)(self.registers, &instruction.operands)
+                .ok_or_else(|| CpuError::Overflow { cycle: self.cycle, instruction: instruction.to_string() })?;
+        }
+        self.cycle += 1;
+        Ok(Some(tick))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(set: &InstructionSet, input: &str) -> Vec<Tick> {
+        let mut cpu = Cpu::new(set.parse(input).unwrap());
+        let mut ticks = vec![];
+        while let Some(tick) = cpu.tick().unwrap() {
+            ticks.push(tick);
+        }
+        ticks
+    }
+
+    #[test]
+    fn test_parse() {
+        let program = InstructionSet::default().parse("noop\naddx -1\n\nnoop\n").unwrap();
+        let program: Vec<String> = program.iter().map(|instruction| instruction.to_string()).collect();
+        assert_eq!(program, ["noop", "addx -1", "noop"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let set = InstructionSet::default();
+        assert_eq!(set.parse("noop\naddy 1").err(), Some(ParseError::UnknownInstruction { line: 2, name: "addy".to_string() }));
+        assert_eq!(set.parse("addx one").err(), Some(ParseError::InvalidOperand { line: 1, operand: "one".to_string() }));
+        assert_eq!(set.parse("addx 1 2").err(), Some(ParseError::OperandCount { line: 1, expected: 1, found: 2 }));
+        assert!(InstructionSet::extended().parse("addy 1").is_ok());
+    }
+
+    #[test]
+    fn test_small_example() {
+        let ticks = run(&InstructionSet::default(), "noop\naddx 3\naddx -5");
+        let x: Vec<i64> = ticks.iter().map(|tick| tick.registers.x).collect();
+        assert_eq!(x, [1, 1, 1, 4, 4]);
+        assert_eq!(ticks.last().unwrap().cycle, 5);
+    }
+
+    #[test]
+    fn test_extended_instructions() {
+        let ticks = run(&InstructionSet::extended(), "addy 2\nmulx 5\njmp 2\naddx 100\naddx 1");
+        let states: Vec<(i64, i64)> = ticks.iter().map(|tick| (tick.registers.x, tick.registers.y)).collect();
+        // addy, mulx and jmp take 2, 3 and 1 cycles, the jump skips `addx 100`
+        assert_eq!(states, [(1, 0), (1, 0), (1, 2), (1, 2), (1, 2), (5, 2), (5, 2), (5, 2)]);
+    }
+
+    #[test]
+    fn test_cycle_limit() {
+        let mut cpu = Cpu::new(InstructionSet::extended().parse("noop\njmp 0").unwrap());
+        cpu.max_cycles = 100;
+        let mut ticks = 0;
+        let error = loop {
+            match cpu.tick() {
+                Ok(Some(_)) => ticks += 1,
+                Ok(None) => panic!("A looping program can't halt"),
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(error, CpuError::CycleLimit(100));
+        assert_eq!(ticks, 100);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let set = InstructionSet::extended();
+        let mut cpu = Cpu::new(set.parse("addx 2\nmulx 9223372036854775807").unwrap());
+        let error = loop {
+            if let Err(e) = cpu.tick() {
+                break e;
+            }
+        };
+        assert_eq!(error, CpuError::Overflow { cycle: 5, instruction: "mulx 9223372036854775807".to_string() });
+
+        let mut cpu = Cpu::new(set.parse("addy -9223372036854775808\naddy -1").unwrap());
+        assert!((0..4).map(|_| cpu.tick()).any(|tick| tick.is_err()));
+    }
+
+    #[test]
+    fn test_custom_instruction() {
+        let mut set = InstructionSet::default();
+        set.register(InstructionSpec { name: "swap", operands: 0, cycles: 4, effect: |r, _| Some(Registers { x: r.y, y: r.x, ..r }) });
+        let ticks = run(&set, "swap\nnoop");
+        assert_eq!(ticks.len(), 5);
+        assert_eq!(ticks[3].registers.x, 1);
+        assert_eq!(ticks[4].registers.x, 0);
+    }
+}
@@ -0,0 +1,86 @@
+// Size of the CRT, the puzzle's is 40x6
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Screen {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for Screen {
+    fn default() -> Screen {
+        Screen { width: 40, height: 6 }
+    }
+}
+
+impl Screen {
+    // (row, column) of the pixel drawn during `cycle`, None once the screen is full
+    pub fn position(&self, cycle: usize) -> Option<(usize, usize)> {
+        let index = cycle.checked_sub(1)?;
+        if index >= self.width * self.height {
+            return None;
+        }
+        Some((index / self.width, index % self.width))
+    }
+}
+
+// Screen drawn one pixel per cycle, left to right and top to bottom
+pub struct Crt {
+    pub screen: Screen,
+    pixels: Vec<bool>,
+}
+
+impl Default for Crt {
+    fn default() -> Crt {
+        Crt::new(Screen::default())
+    }
+}
+
+impl Crt {
+    pub fn new(screen: Screen) -> Crt {
+        Crt {
+            screen,
+            pixels: vec![false; screen.width * screen.height],
+        }
+    }
+
+    // Lights the pixel of `cycle` if the three pixels wide sprite centered on `sprite` covers it
+    pub fn draw(&mut self, cycle: usize, sprite: i64) {
+        if let Some((row, column)) = self.screen.position(cycle) {
+            if (column as i64 - sprite).abs() <= 1 {
+                self.pixels[row * self.screen.width + column] = true;
+            }
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        for row in self.pixels.chunks(self.screen.width) {
+            output.extend(row.iter().map(|pixel| if *pixel { '#' } else { '.' }));
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let screen = Screen::default();
+        assert_eq!(screen.position(1), Some((0, 0)));
+        assert_eq!(screen.position(41), Some((1, 0)));
+        assert_eq!(screen.position(240), Some((5, 39)));
+        assert_eq!(screen.position(241), None);
+        assert_eq!(screen.position(0), None);
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut crt = Crt::new(Screen { width: 4, height: 1 });
+        for cycle in 1..=5 {
+            crt.draw(cycle, 2);
+        }
+        assert_eq!(crt.render(), ".###\n");
+    }
+}
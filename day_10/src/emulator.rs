@@ -0,0 +1,83 @@
+use crate::{cpu::{Cpu, CpuError, Instruction, Registers, Tick}, crt::Screen};
+
+// What observers are shown during each cycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cycle {
+    pub tick: Tick,
+    // (row, column) the CRT draws during this cycle, None past the end of the screen
+    pub pixel: Option<(usize, usize)>,
+}
+
+type Observer<'o> = Box<dyn FnMut(&Cycle) + 'o>;
+
+// Runs a CPU and calls every registered observer once per cycle, in registration order
+pub struct Emulator<'o> {
+    cpu: Cpu,
+    screen: Screen,
+    observers: Vec<Observer<'o>>,
+}
+
+impl<'o> Emulator<'o> {
+    // Pixel positions are given for `screen`, usually the one of the `Crt` being drawn
+    pub fn new(program: Vec<Instruction>, screen: Screen) -> Emulator<'o> {
+        Emulator {
+            cpu: Cpu::new(program),
+            screen,
+            observers: vec![],
+        }
+    }
+
+    // Cycles after which `run` gives up, `cpu::MAX_CYCLES` by default
+    pub fn max_cycles(mut self, max_cycles: usize) -> Emulator<'o> {
+        self.cpu.max_cycles = max_cycles;
+        self
+    }
+
+    pub fn observe<F: FnMut(&Cycle) + 'o>(&mut self, observer: F) {
+        self.observers.push(Box::new(observer));
+    }
+
+    // Runs until the program halts and returns the final registers
+    pub fn run(mut self) -> Result<Registers, CpuError> {
+        while let Some(tick) = self.cpu.tick()? {
+            let cycle = Cycle { tick, pixel: self.screen.position(tick.cycle) };
+            for observer in self.observers.iter_mut() {
+                observer(&cycle);
+            }
+        }
+        Ok(self.cpu.registers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::InstructionSet;
+
+    #[test]
+    fn test_observers() {
+        let program = InstructionSet::default().parse("noop\naddx 3\naddx -5").unwrap();
+        let mut seen = vec![];
+        let mut count = 0;
+        let mut emulator = Emulator::new(program, Screen { width: 2, height: 2 });
+        emulator.observe(|cycle| seen.push((cycle.tick.cycle, cycle.tick.registers.x, cycle.pixel)));
+        emulator.observe(|_| count += 1);
+        let registers = emulator.run().unwrap();
+
+        assert_eq!(registers.x, -1);
+        assert_eq!(count, 5);
+        assert_eq!(seen, [
+            (1, 1, Some((0, 0))), (2, 1, Some((0, 1))), (3, 1, Some((1, 0))), (4, 4, Some((1, 1))), (5, 4, None),
+        ]);
+    }
+
+    #[test]
+    fn test_max_cycles() {
+        let program = InstructionSet::extended().parse("noop\njmp 0").unwrap();
+        let mut count = 0;
+        let mut emulator = Emulator::new(program, Screen::default()).max_cycles(1000);
+        emulator.observe(|_| count += 1);
+        assert_eq!(emulator.run(), Err(CpuError::CycleLimit(1000)));
+        assert_eq!(count, 1000);
+    }
+}
@@ -1,116 +1,75 @@
+use std::env;
 
-fn main() {
-    let input = load_file("data/input.txt");
-    let mut processor = Processor::new();
-    let commands = parse_commands(&input);
-    let mut result: i64 = 0;
-    for command in commands {
-        match command {
-            Command::Noop => {
-                let value = processor.process_noop();
-                if value.is_some() {
-                    result += value.unwrap();
-                }
-            },
-            Command::AddX(value) => {
-                let value = processor.process_addx(value);
-                if value.is_some() {
-                    result += value.unwrap();
-                }
-            },
-        }
-    }
-    println!("Result: {}", result);
-    processor.draw();
-}
+use cpu::{InstructionSet, MAX_CYCLES};
+use crt::Crt;
+use emulator::Emulator;
+use observers::{trace_line, Schedule, SignalSampler};
 
-struct Processor {
-    cycle: i64,
-    register: i64,
-    screen: Vec<Vec<bool>>,
-}
+mod cpu;
+mod crt;
+mod emulator;
+mod observers;
 
-impl Processor {
+// Extra instructions (addy, mulx, jmp): `cargo run -- --extended <file> [max cycles]`
+// Tracing: `cargo run -- --trace [first cycle] [interval]`
+// Sampling: `cargo run -- --sample <cycle,cycle,...>`
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mode = args.get(1).map(|arg| arg.as_str());
+    let (instruction_set, path) = match mode {
+        Some("--extended") => (InstructionSet::extended(), args.get(2).map(|arg| arg.as_str()).unwrap_or("data/input.txt")),
+        _ => (InstructionSet::default(), "data/input.txt"),
+    };
+    let input = load_file(path);
+    let max_cycles = match mode {
+        Some("--extended") => args.get(3).map(|arg| arg.parse::<usize>().expect("Invalid max cycles")).unwrap_or(MAX_CYCLES),
+        _ => MAX_CYCLES,
+    };
 
-    pub fn new() -> Self {
-        Processor {
-            cycle: 1,
-            register: 1,
-            screen: vec![vec![false; 40]; 6],
-        }
-    }
-    
-    fn check_for_sampling(self: &Self) -> bool {
-        (self.cycle - 20) % 40 == 0
-    }
-    
-    pub fn process_noop(self: &mut Self) -> Option<i64> {
-        let mut result: Option<i64> = None;
-        self.process(&mut result);
-        result
-    }
-    
-    pub fn process_addx(self: &mut Self, value: i64) -> Option<i64> {
-        let mut result: Option<i64> = None;
-        self.process(&mut result);
-        self.process(&mut result);
-        self.register += value;
-        result
-    }
+    let schedule = match mode {
+        Some("--sample") => Schedule::At(args.get(2).expect("Missing cycles")
+            .split(',')
+            .map(|cycle| cycle.parse::<usize>().expect("Invalid cycle"))
+            .collect()),
+        _ => Schedule::default(),
+    };
+    let trace = match mode {
+        Some("--trace") => Some(Schedule::Every {
+            first: args.get(2).map(|arg| arg.parse::<usize>().expect("Invalid first cycle")).unwrap_or(1),
+            interval: args.get(3).map(|arg| arg.parse::<usize>().expect("Invalid interval")).unwrap_or(1),
+        }),
+        _ => None,
+    };
 
-    fn process(self: &mut Self, result: &mut Option<i64>) {
-        if self.check_for_sampling() {
-            *result = Some(self.register * self.cycle);
-        }
-        let sprite_pixels = vec![self.register -1, self.register, self.register + 1];
-        let current_pixel = (self.cycle - 1) % 40;
-        if sprite_pixels.contains(&current_pixel) {
-            self.draw_pixel();
-        }
-        self.cycle += 1;
-    }
+    let (sampler, crt) = run(&instruction_set, &input, schedule, trace, max_cycles);
+    println!("Result: {}", sampler.total());
+    print!("{}", crt.render());
+}
 
-    fn draw_pixel(self: &mut Self) {
-        let x = (self.cycle - 1) / 40 ;
-        let y = (self.cycle - 1) % 40 ;
-        self.screen[x as usize][y as usize] = true;
-    }
+// Signal strengths sampled on `schedule` and the screen drawn by the program,
+// printing a trace line on the cycles of `trace`
+fn run(instruction_set: &InstructionSet, input: &str, schedule: Schedule, trace: Option<Schedule>, max_cycles: usize) -> (SignalSampler, Crt) {
+    let program = match instruction_set.parse(input) {
+        Ok(program) => program,
+        Err(e) => panic!("{}", e),
+    };
+    let mut sampler = SignalSampler::new(schedule);
+    let mut crt = Crt::default();
 
-    pub fn draw(self: &Self) {
-        for row in &self.screen {
-            for pixel in row {
-                if *pixel {
-                    print!("#");
-                } else {
-                    print!(".");
-                }
+    let mut emulator = Emulator::new(program, crt.screen).max_cycles(max_cycles);
+    emulator.observe(|cycle| sampler.observe(cycle));
+    emulator.observe(|cycle| crt.draw(cycle.tick.cycle, cycle.tick.registers.x));
+    if let Some(trace) = trace {
+        emulator.observe(move |cycle| {
+            if trace.includes(cycle.tick.cycle) {
+                println!("{}", trace_line(cycle));
             }
-            println!("");
-        }
+        });
     }
-}
-
-#[derive(PartialEq, Debug)]
-enum Command {
-    Noop,
-    AddX(i64),
-}
-
-fn parse_commands(input: &str) -> Vec<Command> {
-    let mut result: Vec<Command> = Vec::new();
-    for line in input.lines() {
-        let mut parts = line.split_whitespace();
-        let command = parts.next().unwrap();
-        match command {
-            "noop" => result.push(Command::Noop),
-            "addx" => {
-                let value = parts.next().unwrap().parse::<i64>().unwrap();
-                result.push(Command::AddX(value));
-            },
-            _ => panic!("Unknown command: {}", command),
-        }
+    if let Err(e) = emulator.run() {
+        panic!("{}", e);
     }
-    result
+    (sampler, crt)
 }
 
 fn load_file(filename: &str) -> String {
@@ -127,12 +86,17 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_commands() {
-        let input = "noop\naddx 1\nnoop";
-        let commands = parse_commands(input);
-        assert_eq!(commands.len(), 3);
-        assert_eq!(commands[0], Command::Noop);
-        assert_eq!(commands[1], Command::AddX(1));
-        assert_eq!(commands[2], Command::Noop);
+    fn test_example() {
+        let input = load_file("data/test.txt");
+        let (sampler, crt) = run(&InstructionSet::default(), &input, Schedule::default(), None, MAX_CYCLES);
+        assert_eq!(sampler.total(), 13140);
+        assert_eq!(sampler.samples, [(20, 420), (60, 1140), (100, 1800), (140, 2940), (180, 2880), (220, 3960)]);
+        assert_eq!(crt.render(), "\
+            ##..##..##..##..##..##..##..##..##..##..\n\
+            ###...###...###...###...###...###...###.\n\
+            ####....####....####....####....####....\n\
+            #####.....#####.....#####.....#####.....\n\
+            ######......######......######......####\n\
+            #######.......#######.......#######.....\n");
     }
 }
@@ -0,0 +1,99 @@
+use crate::emulator::Cycle;
+
+// Cycles an observer reacts to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Every { first: usize, interval: usize },
+    At(Vec<usize>),
+}
+
+impl Default for Schedule {
+    // Cycles 20, 60, 100... like the puzzle statement
+    fn default() -> Schedule {
+        Schedule::Every { first: 20, interval: 40 }
+    }
+}
+
+impl Schedule {
+    pub fn includes(&self, cycle: usize) -> bool {
+        match self {
+            // An interval of 0 only matches the first cycle
+            Schedule::Every { first, interval: 0 } => cycle == *first,
+            Schedule::Every { first, interval } => cycle >= *first && (cycle - first).is_multiple_of(*interval),
+            Schedule::At(cycles) => cycles.contains(&cycle),
+        }
+    }
+}
+
+// Collects the signal strength, cycle times X, on the cycles of its schedule
+#[derive(Debug, Default)]
+pub struct SignalSampler {
+    pub schedule: Schedule,
+    pub samples: Vec<(usize, i64)>,
+}
+
+impl SignalSampler {
+    pub fn new(schedule: Schedule) -> SignalSampler {
+        SignalSampler { schedule, samples: vec![] }
+    }
+
+    pub fn observe(&mut self, cycle: &Cycle) {
+        if self.schedule.includes(cycle.tick.cycle) {
+            self.samples.push((cycle.tick.cycle, cycle.tick.cycle as i64 * cycle.tick.registers.x));
+        }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.samples.iter().map(|(_, strength)| strength).sum()
+    }
+}
+
+pub fn trace_line(cycle: &Cycle) -> String {
+    let pixel = match cycle.pixel {
+        Some((row, column)) => format!("({}, {})", row, column),
+        None => "-".to_string(),
+    };
+    format!("cycle {:>4}  X {:>4}  Y {:>4}  pc {:>4}  pixel {}",
+        cycle.tick.cycle, cycle.tick.registers.x, cycle.tick.registers.y, cycle.tick.registers.pc, pixel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::{Registers, Tick};
+
+    fn cycle(cycle: usize, x: i64) -> Cycle {
+        Cycle { tick: Tick { cycle, registers: Registers { x, ..Registers::default() } }, pixel: None }
+    }
+
+    #[test]
+    fn test_schedule() {
+        let schedule = Schedule::default();
+        assert!(schedule.includes(20));
+        assert!(schedule.includes(220));
+        assert!(!schedule.includes(19));
+        assert!(!schedule.includes(40));
+        assert!(Schedule::Every { first: 5, interval: 0 }.includes(5));
+        assert!(!Schedule::Every { first: 5, interval: 0 }.includes(10));
+        assert!(Schedule::At(vec![3, 7]).includes(7));
+    }
+
+    #[test]
+    fn test_signal_sampler() {
+        let mut sampler = SignalSampler::new(Schedule::At(vec![2, 3]));
+        for (n, x) in [(1, 1), (2, 5), (3, -2), (4, 10)] {
+            sampler.observe(&cycle(n, x));
+        }
+        assert_eq!(sampler.samples, [(2, 10), (3, -6)]);
+        assert_eq!(sampler.total(), 4);
+    }
+
+    #[test]
+    fn test_trace_line() {
+        let line = trace_line(&Cycle {
+            tick: Tick { cycle: 41, registers: Registers { x: 3, y: 0, pc: 7 } },
+            pixel: Some((1, 0)),
+        });
+        assert_eq!(line, "cycle   41  X    3  Y    0  pc    7  pixel (1, 0)");
+    }
+}
@@ -1,11 +1,47 @@
-use std::fs;
+use std::{env, fmt, fs::{self, File}, io};
 
+mod planner;
+mod renderer;
+
+#[derive(Clone, Debug, PartialEq)]
 struct Instruction {
     amount: usize,
     from: usize,
     to: usize,
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Stacks are stored 0-based but the input format is 1-based
+        write!(f, "move {} from {} to {}", self.amount, self.from + 1, self.to + 1)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Crane {
+    // Moves boxes one at a time
+    OneByOne,
+    // Moves a whole group of boxes at once, keeping their order
+    MultipleAtOnce,
+}
+
+impl Crane {
+    fn from_arg(arg: Option<&str>) -> Crane {
+        match arg {
+            Some("one-by-one") => Crane::OneByOne,
+            Some("at-once") | None => Crane::MultipleAtOnce,
+            Some(other) => panic!("Unknown crane: {}", other),
+        }
+    }
+
+    fn apply(self, boxes: &mut [Vec<char>], instruction: &Instruction) {
+        match self {
+            Crane::OneByOne => apply_instruction(boxes, instruction),
+            Crane::MultipleAtOnce => apply_instruction_with_crane(boxes, instruction),
+        }
+    }
+}
+
 fn main() {
     let input = read_file();
     let (boxes_part, instructions_part) = separate_box_instructions(input);
@@ -29,7 +65,39 @@ fn main() {
     println!("Boxes after executing instructions with crane:");
     print_top_of_pile(boxes_copy);
 
-
+    // Step mode: `cargo run -- --step <one-by-one|at-once> [output file]`
+    // Planner: `cargo run -- --plan <one-by-one|at-once> <top of piles> [max moves]`
+    //          `cargo run -- --plan-file <one-by-one|at-once> <diagram file> [max moves]`
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("--step") => {
+            let crane = Crane::from_arg(args.get(2).map(|arg| arg.as_str()));
+            let result = match args.get(3) {
+                Some(path) => File::create(path)
+                    .and_then(|mut file| renderer::animate(boxes.clone(), &instructions, crane, &mut file)),
+                None => renderer::animate(boxes.clone(), &instructions, crane, &mut io::stdout()),
+            };
+            if let Err(e) = result {
+                panic!("Could not render steps: {}", e);
+            }
+        },
+        Some(mode @ ("--plan" | "--plan-file")) => {
+            let crane = Crane::from_arg(args.get(2).map(|arg| arg.as_str()));
+            let target_arg = args.get(3).expect("Missing target");
+            let target = if mode == "--plan" {
+                planner::Target::TopOfPile(target_arg.to_string())
+            } else {
+                let diagram = fs::read_to_string(target_arg).expect("Could not read target diagram");
+                planner::Target::Diagram(parse_boxes(parse_box_lines(diagram)))
+            };
+            let max_moves = args.get(4).map(|arg| arg.parse::<usize>().expect("Invalid max moves")).unwrap_or(3);
+            match planner::plan(&boxes, &target, crane, max_moves) {
+                Some(plan) => print!("{}", planner::format_plan(&plan)),
+                None => println!("No plan found within {} moves and {} states", max_moves, planner::MAX_STATES),
+            }
+        },
+        _ => {},
+    }
 }
 
 fn separate_box_instructions(input: String) -> (String, String) {
@@ -81,7 +149,7 @@ fn parse_box_lines(boxes: String) -> Vec<Vec<char>> {
         }
 
         // Skip empty lines
-        if parsed_line.len() == 0 {
+        if parsed_line.is_empty() {
             continue;
         }
 
@@ -106,28 +174,36 @@ fn parse_instructions(input: String) -> Vec<Instruction> {
     }).collect()
 }
 
+fn apply_instruction(boxes: &mut [Vec<char>], instruction: &Instruction) {
+    for _ in 0..instruction.amount {
+        let popped_box = boxes[instruction.from].pop();
+        boxes[instruction.to].push(popped_box.unwrap());
+    }
+}
+
+fn apply_instruction_with_crane(boxes: &mut [Vec<char>], instruction: &Instruction) {
+    let drain_from = boxes[instruction.from].len() - instruction.amount;
+    let mut moved_boxes = boxes[instruction.from].drain(drain_from..).collect();
+    boxes[instruction.to].append(&mut moved_boxes);
+}
+
 fn execute_instructions(mut boxes: Vec<Vec<char>>, instructions: &Vec<Instruction>) -> Vec<Vec<char>> {
     for instruction in instructions {
-        for _ in 0..instruction.amount {
-            let popped_box = boxes[instruction.from].pop();
-            boxes[instruction.to].push(popped_box.unwrap());
-        }
+        apply_instruction(&mut boxes, instruction);
     }
     boxes
 }
 
 fn execute_instructions_with_crane(mut boxes: Vec<Vec<char>>, instructions: &Vec<Instruction>) -> Vec<Vec<char>>{
     for instruction in instructions {
-        let drain_from = boxes[instruction.from].len() - instruction.amount;
-        let mut moved_boxes = boxes[instruction.from].drain(drain_from..).collect();
-        boxes[instruction.to].append(&mut moved_boxes);
+        apply_instruction_with_crane(&mut boxes, instruction);
     }
     boxes
 }
 
 fn print_top_of_pile(boxes: Vec<Vec<char>>) {
     for box_pile in boxes {
-        if box_pile.len() == 0 {
+        if box_pile.is_empty() {
             continue;
         }
         print!("{}", box_pile.last().unwrap());
@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{Crane, Instruction};
+
+pub(crate) enum Target {
+    // Top box of every non-empty pile, as printed by `print_top_of_pile`
+    TopOfPile(String),
+    // Complete arrangement of every pile
+    Diagram(Vec<Vec<char>>),
+}
+
+impl Target {
+    fn is_reached(&self, boxes: &[Vec<char>]) -> bool {
+        match self {
+            Target::TopOfPile(tops) => {
+                let current: String = boxes.iter().filter_map(|pile| pile.last()).collect();
+                &current == tops
+            },
+            Target::Diagram(diagram) => boxes == diagram.as_slice(),
+        }
+    }
+
+    // Whether some arrangement of these boxes matches at all, checked before searching
+    fn is_feasible(&self, boxes: &[Vec<char>]) -> bool {
+        match self {
+            Target::TopOfPile(tops) => {
+                // Moves never add or remove boxes, so there is one top per non-empty pile
+                let total = boxes.iter().map(|pile| pile.len()).sum::<usize>();
+                let count = tops.chars().count();
+                let available = letter_counts(boxes.iter().flatten().copied());
+                count <= boxes.len().min(total) && (count > 0 || total == 0)
+                    && letter_counts(tops.chars()).iter().all(|(c, n)| available.get(c).unwrap_or(&0) >= n)
+            },
+            // Moves never add or remove boxes, so different contents can never match
+            Target::Diagram(diagram) => diagram.len() == boxes.len() && sorted_boxes(diagram) == sorted_boxes(boxes),
+        }
+    }
+
+    // Minimum number of moves left, a move changes at most two piles and two tops
+    fn lower_bound(&self, boxes: &[Vec<char>]) -> usize {
+        let changes = match self {
+            Target::TopOfPile(tops) => {
+                let mut current = letter_counts(boxes.iter().filter_map(|pile| pile.last()).copied());
+                tops.chars()
+                    .filter(|c| match current.get_mut(c) {
+                        Some(n) if *n > 0 => {
+                            *n -= 1;
+                            false
+                        },
+                        _ => true,
+                    })
+                    .count()
+            },
+            Target::Diagram(diagram) => boxes.iter().zip(diagram).filter(|(pile, target)| pile != target).count(),
+        };
+        changes.div_ceil(2)
+    }
+}
+
+struct Node {
+    boxes: Vec<Vec<char>>,
+    parent: Option<usize>,
+    instruction: Option<Instruction>,
+    depth: usize,
+}
+
+// Stops the search before it runs out of memory, each state on a 9 pile input has
+// hundreds of possible moves
+pub(crate) const MAX_STATES: usize = 100_000;
+
+// Breadth-first search over crane moves, so the first plan found is one of the shortest.
+// States that can't reach the target within `max_moves` are pruned, and the search gives
+// up once `MAX_STATES` states have been generated.
+pub(crate) fn plan(boxes: &[Vec<char>], target: &Target, crane: Crane, max_moves: usize) -> Option<Vec<Instruction>> {
+    if !target.is_feasible(boxes) {
+        return None;
+    }
+
+    let mut arena = vec![Node {
+        boxes: boxes.to_vec(),
+        parent: None,
+        instruction: None,
+        depth: 0,
+    }];
+    let mut visited: HashSet<Vec<Vec<char>>> = HashSet::new();
+    visited.insert(boxes.to_vec());
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(current) = queue.pop_front() {
+        if target.is_reached(&arena[current].boxes) {
+            return Some(collect_plan(&arena, current));
+        }
+        if arena[current].depth == max_moves {
+            continue;
+        }
+
+        for instruction in possible_instructions(&arena[current].boxes) {
+            let mut next = arena[current].boxes.clone();
+            crane.apply(&mut next, &instruction);
+            let depth = arena[current].depth + 1;
+            if depth + target.lower_bound(&next) > max_moves || !visited.insert(next.clone()) {
+                continue;
+            }
+            if arena.len() == MAX_STATES {
+                return None;
+            }
+            arena.push(Node {
+                boxes: next,
+                parent: Some(current),
+                instruction: Some(instruction),
+                depth,
+            });
+            queue.push_back(arena.len() - 1);
+        }
+    }
+    None
+}
+
+// Formats a plan the same way as the puzzle input, so it can be fed back to `parse_instructions`
+pub(crate) fn format_plan(instructions: &[Instruction]) -> String {
+    instructions.iter().map(|instruction| format!("{}\n", instruction)).collect()
+}
+
+fn possible_instructions(boxes: &[Vec<char>]) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    for (from, pile) in boxes.iter().enumerate() {
+        for to in 0..boxes.len() {
+            if from == to {
+                continue;
+            }
+            for amount in 1..=pile.len() {
+                instructions.push(Instruction { amount, from, to });
+            }
+        }
+    }
+    instructions
+}
+
+fn collect_plan(arena: &[Node], mut current: usize) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    while let Some(parent) = arena[current].parent {
+        if let Some(instruction) = &arena[current].instruction {
+            instructions.push(instruction.clone());
+        }
+        current = parent;
+    }
+    instructions.reverse();
+    instructions
+}
+
+fn letter_counts<I: Iterator<Item = char>>(letters: I) -> HashMap<char, usize> {
+    let mut counts = HashMap::new();
+    for letter in letters {
+        *counts.entry(letter).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn sorted_boxes(boxes: &[Vec<char>]) -> Vec<char> {
+    let mut all: Vec<char> = boxes.iter().flatten().copied().collect();
+    all.sort();
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_boxes() -> Vec<Vec<char>> {
+        vec!(vec!('Z', 'N'), vec!('M', 'C', 'D'), vec!('P'))
+    }
+
+    #[test]
+    fn test_plan_top_of_pile() {
+        let boxes = test_boxes();
+        let plan = plan(&boxes, &Target::TopOfPile("DCP".to_string()), Crane::OneByOne, 3).unwrap();
+        assert_eq!(plan, vec!(Instruction { amount: 1, from: 1, to: 0 }));
+    }
+
+    #[test]
+    fn test_plan_already_reached() {
+        let boxes = test_boxes();
+        let plan = plan(&boxes, &Target::TopOfPile("NDP".to_string()), Crane::OneByOne, 3).unwrap();
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_plan_diagram_round_trip() {
+        let boxes = test_boxes();
+        let target = vec!(vec!('C'), vec!('M'), vec!('P', 'D', 'N', 'Z'));
+        for crane in [Crane::OneByOne, Crane::MultipleAtOnce] {
+            let plan = plan(&boxes, &Target::Diagram(target.clone()), crane, 4).unwrap();
+            let instructions = crate::parse_instructions(format_plan(&plan));
+            let mut result = boxes.clone();
+            for instruction in &instructions {
+                crane.apply(&mut result, instruction);
+            }
+            assert_eq!(result, target);
+        }
+    }
+
+    #[test]
+    fn test_plan_impossible() {
+        let boxes = test_boxes();
+        assert!(plan(&boxes, &Target::TopOfPile("XYZ".to_string()), Crane::OneByOne, 2).is_none());
+        let target = vec!(vec!('Z'), vec!(), vec!());
+        assert!(plan(&boxes, &Target::Diagram(target), Crane::OneByOne, 5).is_none());
+        // Only one N and at most three tops
+        assert!(plan(&boxes, &Target::TopOfPile("NN".to_string()), Crane::OneByOne, 5).is_none());
+        assert!(plan(&boxes, &Target::TopOfPile("ZNCD".to_string()), Crane::OneByOne, 5).is_none());
+        assert!(plan(&boxes, &Target::TopOfPile(String::new()), Crane::OneByOne, 5).is_none());
+    }
+
+    #[test]
+    fn test_lower_bound() {
+        let boxes = test_boxes();
+        assert_eq!(Target::TopOfPile("NDP".to_string()).lower_bound(&boxes), 0);
+        assert_eq!(Target::TopOfPile("ZMC".to_string()).lower_bound(&boxes), 2);
+        let target = vec!(vec!('C'), vec!('M'), vec!('P', 'D', 'N', 'Z'));
+        assert_eq!(Target::Diagram(target).lower_bound(&boxes), 2);
+    }
+
+    // Default settings of the CLI on the real 9 pile input
+    #[test]
+    fn test_plan_real_input() {
+        let (boxes_part, _) = crate::separate_box_instructions(crate::read_file());
+        let boxes = crate::parse_boxes(crate::parse_box_lines(boxes_part));
+        assert_eq!(boxes.len(), 9);
+
+        let mut expected = boxes.clone();
+        crate::apply_instruction(&mut expected, &Instruction { amount: 2, from: 3, to: 4 });
+        crate::apply_instruction(&mut expected, &Instruction { amount: 1, from: 0, to: 8 });
+        let tops: String = expected.iter().filter_map(|pile| pile.last()).collect();
+        let found = plan(&boxes, &Target::TopOfPile(tops.clone()), Crane::OneByOne, 3).unwrap();
+        assert!(found.len() <= 2);
+        let mut result = boxes.clone();
+        for instruction in &found {
+            crate::apply_instruction(&mut result, instruction);
+        }
+        assert!(Target::TopOfPile(tops).is_reached(&result));
+
+        // There are only three Q boxes, rejected without searching
+        assert!(plan(&boxes, &Target::TopOfPile("QQQQQQQQQ".to_string()), Crane::OneByOne, 3).is_none());
+
+        // Bottom boxes of the first six piles on top, far more than 3 moves away but not
+        // ruled out by the lower bound, so the search has to stop on its own
+        let tops: String = boxes.iter().enumerate()
+            .map(|(i, pile)| if i < 6 { pile[0] } else { *pile.last().unwrap() })
+            .collect();
+        assert_eq!(Target::TopOfPile(tops.clone()).lower_bound(&boxes), 3);
+        for crane in [Crane::OneByOne, Crane::MultipleAtOnce] {
+            assert!(plan(&boxes, &Target::TopOfPile(tops.clone()), crane, 3).is_none());
+        }
+    }
+}
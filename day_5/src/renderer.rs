@@ -0,0 +1,69 @@
+use std::io::{self, Write};
+
+use crate::{Crane, Instruction};
+
+// Draws the stacks in the same format as the puzzle input, index row included
+pub(crate) fn render_diagram(boxes: &[Vec<char>]) -> String {
+    let height = boxes.iter().map(|pile| pile.len()).max().unwrap_or(0);
+    let mut diagram = String::new();
+
+    for level in (0..height).rev() {
+        let line: Vec<String> = boxes.iter().map(|pile| {
+            match pile.get(level) {
+                Some(c) => format!("[{}]", c),
+                None => "   ".to_string(),
+            }
+        }).collect();
+        diagram.push_str(&line.join(" "));
+        diagram.push('\n');
+    }
+
+    let index_row: Vec<String> = (1..=boxes.len()).map(|i| format!(" {} ", i)).collect();
+    diagram.push_str(&index_row.join(" "));
+    diagram.push('\n');
+    diagram
+}
+
+// Writes the initial diagram, then every instruction followed by the resulting diagram
+pub(crate) fn animate<W: Write>(mut boxes: Vec<Vec<char>>, instructions: &[Instruction], crane: Crane, out: &mut W) -> io::Result<Vec<Vec<char>>> {
+    writeln!(out, "{}", render_diagram(&boxes))?;
+    for (step, instruction) in instructions.iter().enumerate() {
+        crane.apply(&mut boxes, instruction);
+        writeln!(out, "Step {}: {}", step + 1, instruction)?;
+        writeln!(out, "{}", render_diagram(&boxes))?;
+    }
+    Ok(boxes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIAGRAM: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+
+    #[test]
+    fn test_render_diagram() {
+        let boxes = vec!(vec!('Z', 'N'), vec!('M', 'C', 'D'), vec!('P'));
+        assert_eq!(render_diagram(&boxes), DIAGRAM);
+    }
+
+    #[test]
+    fn test_render_diagram_round_trip() {
+        let boxes = crate::parse_boxes(crate::parse_box_lines(DIAGRAM.to_string()));
+        assert_eq!(render_diagram(&boxes), DIAGRAM);
+    }
+
+    #[test]
+    fn test_animate() {
+        let boxes = vec!(vec!('Z', 'N'), vec!('M', 'C', 'D'), vec!('P'));
+        let instructions = crate::parse_instructions("move 1 from 2 to 1\nmove 3 from 1 to 3\n".to_string());
+        let mut out: Vec<u8> = Vec::new();
+        let boxes = animate(boxes, &instructions, Crane::OneByOne, &mut out).unwrap();
+        let output = String::from_utf8(out).unwrap();
+
+        assert!(output.starts_with(DIAGRAM));
+        assert!(output.contains("Step 1: move 1 from 2 to 1\n[D]        \n[N] [C]    \n[Z] [M] [P]\n"));
+        assert!(output.contains("Step 2: move 3 from 1 to 3\n"));
+        assert_eq!(boxes, vec!(vec!(), vec!('M', 'C'), vec!('P', 'D', 'N', 'Z')));
+    }
+}
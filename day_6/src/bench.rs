@@ -0,0 +1,36 @@
+use std::time::{Duration, Instant};
+
+use crate::{find_packet_start, marker::find_marker};
+
+const INPUT_LENGTH: usize = 100_000;
+
+// Compares the original `find_packet_start` with the counting detector.
+// The input never contains a marker so both implementations scan all of it.
+pub fn run_benchmarks() {
+    println!("{:>8} {:>16} {:>16}", "window", "find_packet_start", "find_marker");
+    for window in [4, 14, 1000] {
+        let input = generate_input(INPUT_LENGTH, window.min(27) - 1);
+
+        let (old_result, old_time) = time(|| find_packet_start(&input, window));
+        let (new_result, new_time) = time(|| find_marker(input.as_bytes(), window));
+        assert_eq!(old_result, None);
+        assert_eq!(new_result, None);
+
+        println!("{:>8} {:>16.2?} {:>16.2?}", window, old_time, new_time);
+    }
+}
+
+fn time<T, F: Fn() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+// Pseudo-random lowercase letters drawn from the first `alphabet` letters
+fn generate_input(length: usize, alphabet: usize) -> String {
+    let mut state: u64 = 0x2545F4914F6CDD1D;
+    (0..length).map(|_| {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (b'a' + ((state >> 33) % alphabet as u64) as u8) as char
+    }).collect()
+}
@@ -1,12 +1,65 @@
-use std::{fs::File, io::Read, collections::VecDeque};
+use std::{env, fs::File, io::{self, Read}, collections::VecDeque, thread};
+
+mod bench;
+mod marker;
+mod streams;
+
+use marker::{find_all_markers, find_marker, find_marker_in_reader};
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("--bench") => {
+            bench::run_benchmarks();
+            return;
+        },
+        // Scan mode: `cargo run -- --scan <marker size>` lists every marker position
+        Some("--scan") => {
+            let size = parse_size(args.get(2));
+            let contents = read_file("data/input.txt");
+            let positions = find_all_markers(contents.trim_end().as_bytes(), size);
+            println!("{} markers of size {}", positions.len(), size);
+            for position in positions {
+                println!("{}", position);
+            }
+            return;
+        },
+        // Multi-stream mode: `cargo run -- --lines [path]` decodes every line on its own
+        Some("--lines") => {
+            let contents = read_file(args.get(2).map(|arg| arg.as_str()).unwrap_or("data/input.txt"));
+            let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+            let results = streams::decode_streams(&contents, workers);
+            print!("{}", streams::format_table(&results));
+            return;
+        },
+        // Streaming mode: `cargo run -- --stdin <marker size>` reads the datastream from stdin
+        Some("--stdin") => {
+            let size = parse_size(args.get(2));
+            match find_marker_in_reader(io::stdin().lock(), size).expect("Something went wrong reading stdin") {
+                Some(position) => println!("Marker of size {}: {}", size, position),
+                None => println!("No marker of size {} found", size),
+            }
+            return;
+        },
+        _ => {},
+    }
+
     let contents = read_file("data/input.txt");
-    let packet_start = find_packet_start(&mut contents.clone(), 4);
-    println!("Packet start: {}", packet_start);
+    match find_marker(contents.as_bytes(), 4) {
+        Some(packet_start) => println!("Packet start: {}", packet_start),
+        None => println!("No packet start found"),
+    }
 
-    let message_start = find_packet_start(&mut contents.clone(), 14);
-    println!("Message start: {}", message_start);
+    match find_marker(contents.as_bytes(), 14) {
+        Some(message_start) => println!("Message start: {}", message_start),
+        None => println!("No message start found"),
+    }
+}
+
+// Marker size from the command line, 4 if missing
+fn parse_size(arg: Option<&String>) -> usize {
+    arg.map(|arg| arg.parse::<usize>().ok().filter(|size| *size > 0).expect("Marker size must be at least 1"))
+        .unwrap_or(4)
 }
 
 fn read_file(path: &str) -> String {
@@ -16,26 +69,47 @@ fn read_file(path: &str) -> String {
     contents
 }
 
-fn find_packet_start(input: &mut String, packet_size: usize) -> i32 {
-    let mut start = 0;
+// Returns the number of characters read up to and including the first marker,
+// or None if the input does not contain one
+fn find_packet_start(input: &str, packet_size: usize) -> Option<usize> {
     let mut buf = VecDeque::<char>::new();
     for (i, c) in input.chars().enumerate() {
-        if buf.len() < packet_size {
-            buf.push_back(c);
-        } else {
-            if check_unique(&buf) {
-                start = i as i32;
-                break;
-            } else {
-                buf.pop_front();
-                buf.push_back(c);
-            }
+        buf.push_back(c);
+        if buf.len() > packet_size {
+            buf.pop_front();
+        }
+        if buf.len() == packet_size && check_unique(&buf) {
+            return Some(i + 1);
         }
     }
-    start
+    None
 }
 
 fn check_unique(input: &VecDeque<char>) -> bool {
     let set = input.iter().collect::<std::collections::HashSet<_>>();
     set.len() == input.len()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_packet_start() {
+        assert_eq!(find_packet_start("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 4), Some(7));
+        assert_eq!(find_packet_start("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14), Some(19));
+    }
+
+    #[test]
+    fn test_find_packet_start_at_end() {
+        assert_eq!(find_packet_start("aaabcd", 4), Some(6));
+        assert_eq!(find_packet_start("abcd", 4), Some(4));
+    }
+
+    #[test]
+    fn test_find_packet_start_missing() {
+        assert_eq!(find_packet_start("aaaaaa", 4), None);
+        assert_eq!(find_packet_start("abc", 4), None);
+        assert_eq!(find_packet_start("", 4), None);
+    }
+}
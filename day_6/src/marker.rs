@@ -0,0 +1,141 @@
+use std::{collections::VecDeque, io::{self, BufReader, Read}};
+
+// Sliding window that keeps a count per byte value and the number of values
+// present more than once, so each new byte is handled in constant time
+// regardless of the window size.
+pub struct MarkerDetector {
+    size: usize,
+    window: VecDeque<u8>,
+    counts: [usize; 256],
+    duplicates: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    // Panics if `size` is 0, an empty window would match before any byte is read
+    pub fn new(size: usize) -> MarkerDetector {
+        assert!(size > 0, "Marker size must be at least 1");
+        MarkerDetector {
+            size,
+            window: VecDeque::with_capacity(size + 1),
+            counts: [0; 256],
+            duplicates: 0,
+            position: 0,
+        }
+    }
+
+    // Number of bytes pushed so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    // Adds a byte to the window and returns true if the window now holds `size` distinct bytes
+    pub fn push(&mut self, byte: u8) -> bool {
+        self.position += 1;
+        self.window.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+
+        if self.window.len() > self.size {
+            if let Some(old) = self.window.pop_front() {
+                self.counts[old as usize] -= 1;
+                if self.counts[old as usize] == 1 {
+                    self.duplicates -= 1;
+                }
+            }
+        }
+
+        self.window.len() == self.size && self.duplicates == 0
+    }
+}
+
+// Returns the number of bytes read up to and including the first marker
+pub fn find_marker(input: &[u8], size: usize) -> Option<usize> {
+    let mut detector = MarkerDetector::new(size);
+    for byte in input {
+        if detector.push(*byte) {
+            return Some(detector.position());
+        }
+    }
+    None
+}
+
+// Scan mode: returns the end position of every window that forms a marker
+pub fn find_all_markers(input: &[u8], size: usize) -> Vec<usize> {
+    let mut detector = MarkerDetector::new(size);
+    input.iter()
+        .filter_map(|byte| {
+            if detector.push(*byte) {
+                Some(detector.position())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn find_marker_in_reader<R: Read>(reader: R, size: usize) -> io::Result<Option<usize>> {
+    let mut detector = MarkerDetector::new(size);
+    for byte in BufReader::new(reader).bytes() {
+        if detector.push(byte?) {
+            return Ok(Some(detector.position()));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+
+    #[test]
+    fn test_find_marker() {
+        for (input, packet, message) in EXAMPLES {
+            assert_eq!(find_marker(input.as_bytes(), 4), Some(packet));
+            assert_eq!(find_marker(input.as_bytes(), 14), Some(message));
+        }
+    }
+
+    #[test]
+    fn test_find_marker_in_reader() {
+        for (input, packet, message) in EXAMPLES {
+            assert_eq!(find_marker_in_reader(input.as_bytes(), 4).unwrap(), Some(packet));
+            assert_eq!(find_marker_in_reader(input.as_bytes(), 14).unwrap(), Some(message));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Marker size must be at least 1")]
+    fn test_zero_size() {
+        MarkerDetector::new(0);
+    }
+
+    #[test]
+    fn test_find_marker_missing() {
+        assert_eq!(find_marker(b"aaaaaaaa", 2), None);
+        assert_eq!(find_marker(b"abc", 4), None);
+    }
+
+    #[test]
+    fn test_find_marker_at_end() {
+        assert_eq!(find_marker(b"aaabcd", 4), Some(6));
+        assert_eq!(find_marker_in_reader(&b"aaabcd"[..], 4).unwrap(), Some(6));
+    }
+
+    #[test]
+    fn test_find_all_markers() {
+        assert_eq!(find_all_markers(b"aabcdd", 3), vec![4, 5]);
+        assert_eq!(find_all_markers(b"abcabc", 3), vec![3, 4, 5, 6]);
+        assert!(find_all_markers(b"aaaa", 2).is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use std::{sync::{atomic::{AtomicUsize, Ordering}, mpsc}, thread};
+
+use crate::marker::find_marker;
+
+const PACKET_SIZE: usize = 4;
+const MESSAGE_SIZE: usize = 14;
+
+#[derive(Debug, PartialEq)]
+pub struct StreamResult {
+    pub line: usize,
+    pub packet_start: Option<usize>,
+    pub message_start: Option<usize>,
+}
+
+// Treats every non-empty line as an independent datastream and decodes them on a
+// fixed pool of worker threads, each one taking the next unprocessed line.
+pub fn decode_streams(input: &str, workers: usize) -> Vec<StreamResult> {
+    let streams: Vec<(usize, &str)> = input.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, line.trim()))
+        .collect();
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers.max(1) {
+            let sender = sender.clone();
+            let streams = &streams;
+            let next = &next;
+            scope.spawn(move || {
+                while let Some((line, stream)) = streams.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = StreamResult {
+                        line: *line,
+                        packet_start: find_marker(stream.as_bytes(), PACKET_SIZE),
+                        message_start: find_marker(stream.as_bytes(), MESSAGE_SIZE),
+                    };
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    let mut results: Vec<StreamResult> = receiver.into_iter().collect();
+    results.sort_by_key(|result| result.line);
+    results
+}
+
+pub fn format_table(results: &[StreamResult]) -> String {
+    let mut table = format!("{:>6} | {:>13} | {:>13}\n", "line", "packet start", "message start");
+    table.push_str(&format!("{:-<6}-+-{:-<13}-+-{:-<13}\n", "", "", ""));
+    for result in results {
+        table.push_str(&format!(
+            "{:>6} | {:>13} | {:>13}\n",
+            result.line,
+            format_position(result.packet_start),
+            format_position(result.message_start),
+        ));
+    }
+    table
+}
+
+fn format_position(position: Option<usize>) -> String {
+    match position {
+        Some(position) => position.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb\nbvwbjplbgvbhsrlpgdmjqwftvncz\n\naaaa\nnppdvjthqldpwncqszvftbrmjlhg\n";
+
+    #[test]
+    fn test_decode_streams() {
+        for workers in [1, 2, 8] {
+            let results = decode_streams(INPUT, workers);
+            assert_eq!(results, vec![
+                StreamResult { line: 1, packet_start: Some(7), message_start: Some(19) },
+                StreamResult { line: 2, packet_start: Some(5), message_start: Some(23) },
+                StreamResult { line: 4, packet_start: None, message_start: None },
+                StreamResult { line: 5, packet_start: Some(6), message_start: Some(23) },
+            ]);
+        }
+    }
+
+    #[test]
+    fn test_format_table() {
+        let results = decode_streams("aaaa\nabcd", 1);
+        let table = format_table(&results);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2], "     1 |             - |             -");
+        assert_eq!(lines[3], "     2 |             4 |             -");
+    }
+}
@@ -0,0 +1,289 @@
+use std::{cell::Cell, cmp::Reverse, collections::BTreeMap};
+
+use crate::{directory::Directory, filesystem::FileSystem};
+
+pub struct CleanupPlanner {
+    pub total_space: usize,
+    pub required_space: usize,
+}
+
+impl Default for CleanupPlanner {
+    // Capacities from the puzzle statement
+    fn default() -> CleanupPlanner {
+        CleanupPlanner {
+            total_space: 70000000,
+            required_space: 30000000,
+        }
+    }
+}
+
+impl CleanupPlanner {
+    pub fn new(total_space: usize, required_space: usize) -> CleanupPlanner {
+        CleanupPlanner { total_space, required_space }
+    }
+
+    pub fn space_to_free(&self, filesystem: &FileSystem) -> usize {
+        let free_space = self.total_space.saturating_sub(filesystem.root().size);
+        self.required_space.saturating_sub(free_space)
+    }
+
+    // Every directory big enough to free the required space on its own, smallest first
+    pub fn candidates<'f>(&self, filesystem: &'f FileSystem) -> Vec<&'f Directory> {
+        let needed = self.space_to_free(filesystem);
+        let mut candidates = filesystem.find_directories(|directory| directory.size >= needed);
+        candidates.sort_by_key(|directory| directory.size);
+        candidates
+    }
+
+    pub fn smallest_directory<'f>(&self, filesystem: &'f FileSystem) -> Option<&'f Directory> {
+        self.candidates(filesystem).into_iter().next()
+    }
+
+    // Fewest directories, none nested in another, whose deletion frees the required space.
+    // Among sets of that size the one freeing the least space is picked, so nothing more
+    // than needed is deleted. The root is never part of the set since deleting it would
+    // always be enough. On trees too large to search within `MAX_STATES`, the set of that
+    // size freeing the most space is returned instead.
+    pub fn minimum_set<'f>(&self, filesystem: &'f FileSystem) -> Option<Vec<&'f Directory>> {
+        let needed = self.space_to_free(filesystem);
+        if needed == 0 {
+            return Some(vec![]);
+        }
+
+        // The largest total for each count gives the fewest directories that can be enough
+        // Directories under different children of the root never free more than those
+        // children, so taking the largest of them bounds how many directories are needed
+        let mut children: Vec<usize> = filesystem.root().children.iter().map(|child| filesystem.arena[*child].size).collect();
+        children.sort_by_key(|size| Reverse(*size));
+        let mut freed = 0;
+        let max_count = children.iter().position(|size| {
+            freed += size;
+            freed >= needed
+        })? + 1;
+
+        let mut best = best_selections(filesystem, filesystem.root().idx, max_count);
+        let count = best.iter()
+            .position(|best| best.as_ref().map(|(size, _)| *size >= needed).unwrap_or(false))?;
+        let bounds = Bounds::new(filesystem, count, needed);
+        let selection = match all_selections(filesystem, filesystem.root().idx, &bounds) {
+            Some(mut totals) => {
+                let smallest = *totals[count].range(needed..).next()?.0;
+                totals[count].remove(&smallest)?
+            },
+            None => best.swap_remove(count)?.1,
+        };
+
+        let mut directories: Vec<&Directory> = selection.iter().map(|idx| &filesystem.arena[*idx]).collect();
+        directories.sort_by_key(|directory| Reverse(directory.size));
+        Some(directories)
+    }
+}
+
+// For every k up to `max_count`, the largest total size (and the directories) reachable
+// by picking k directories under `idx` where none is nested in another one
+fn best_selections(filesystem: &FileSystem, idx: usize, max_count: usize) -> Vec<Option<(usize, Vec<usize>)>> {
+    let mut best: Vec<Option<(usize, Vec<usize>)>> = vec![Some((0, vec![]))];
+    for child in &filesystem.arena[idx].children {
+        let child_best = best_selections(filesystem, *child, max_count);
+        let length = (best.len() + child_best.len() - 1).min(max_count + 1);
+        let mut merged: Vec<Option<(usize, Vec<usize>)>> = vec![None; length];
+        for (i, current) in best.iter().enumerate() {
+            let Some((size, idxs)) = current else { continue };
+            for (j, other) in child_best.iter().enumerate().take(length - i) {
+                let Some((other_size, other_idxs)) = other else { continue };
+                let total = size + other_size;
+                if merged[i + j].as_ref().map(|(best_size, _)| total > *best_size).unwrap_or(true) {
+                    let mut combined = idxs.clone();
+                    combined.extend(other_idxs);
+                    merged[i + j] = Some((total, combined));
+                }
+            }
+        }
+        best = merged;
+    }
+
+    // Picking the directory itself excludes everything below it
+    let size = filesystem.arena[idx].size;
+    if best.len() < 2 && max_count > 0 {
+        best.push(None);
+    }
+    if filesystem.arena[idx].parent.is_some() && max_count > 0 && best[1].as_ref().map(|(best_size, _)| size > *best_size).unwrap_or(true) {
+        best[1] = Some((size, vec![idx]));
+    }
+    best
+}
+
+// Combinations of totals `minimum_set` tries before settling for the largest set,
+// which keeps time and memory bounded on real directory trees
+pub const MAX_STATES: usize = 1_000_000;
+
+// Limits of the search for the smallest set of `count` directories freeing `needed`
+struct Bounds {
+    count: usize,
+    needed: usize,
+    // Sum of the m largest directories, an upper bound on what m more of them can add
+    largest: Vec<usize>,
+    // Combinations left to try
+    budget: Cell<usize>,
+}
+
+impl Bounds {
+    fn new(filesystem: &FileSystem, count: usize, needed: usize) -> Bounds {
+        let mut sizes: Vec<usize> = filesystem.arena.iter()
+            .filter(|directory| directory.parent.is_some())
+            .map(|directory| directory.size)
+            .collect();
+        sizes.sort_by_key(|size| Reverse(*size));
+        let mut largest = vec![0];
+        for size in sizes.into_iter().take(count) {
+            largest.push(largest[largest.len() - 1] + size);
+        }
+        Bounds { count, needed, largest, budget: Cell::new(MAX_STATES) }
+    }
+
+    // Drops the totals of k directories that can't be enough even with the largest
+    // remaining ones, and above `needed` keeps only the smallest since adding
+    // directories only grows a total
+    fn trim(&self, k: usize, totals: &mut BTreeMap<usize, Vec<usize>>) {
+        let extra = self.largest[(self.count - k).min(self.largest.len() - 1)];
+        let smallest_enough = totals.range(self.needed..).next().map(|(size, _)| *size);
+        totals.retain(|size, _| size + extra >= self.needed && smallest_enough.map(|smallest| *size <= smallest).unwrap_or(true));
+    }
+}
+
+// For every k up to `bounds.count`, the total sizes reachable by picking k directories
+// under `idx` where none is nested in another one, with one selection giving each.
+// None once the budget of `bounds` runs out.
+fn all_selections(filesystem: &FileSystem, idx: usize, bounds: &Bounds) -> Option<Vec<BTreeMap<usize, Vec<usize>>>> {
+    let max_count = bounds.count;
+    let mut totals: Vec<BTreeMap<usize, Vec<usize>>> = vec![BTreeMap::new(); max_count + 1];
+    totals[0].insert(0, vec![]);
+    for child in &filesystem.arena[idx].children {
+        let child_totals = all_selections(filesystem, *child, bounds)?;
+        let mut merged: Vec<BTreeMap<usize, Vec<usize>>> = vec![BTreeMap::new(); max_count + 1];
+        for (i, current) in totals.iter().enumerate() {
+            for (j, other) in child_totals.iter().enumerate().take(max_count + 1 - i) {
+                let combinations = current.len() * other.len();
+                bounds.budget.set(bounds.budget.get().checked_sub(combinations)?);
+                for (size, idxs) in current {
+                    for (other_size, other_idxs) in other {
+                        merged[i + j].entry(size + other_size).or_insert_with(|| {
+                            let mut combined = idxs.clone();
+                            combined.extend(other_idxs);
+                            combined
+                        });
+                    }
+                }
+            }
+        }
+        totals = merged;
+        for (k, current) in totals.iter_mut().enumerate() {
+            bounds.trim(k, current);
+        }
+    }
+
+    // Picking the directory itself excludes everything below it
+    if filesystem.arena[idx].parent.is_some() && max_count > 0 {
+        totals[1].entry(filesystem.arena[idx].size).or_insert_with(|| vec![idx]);
+        bounds.trim(1, &mut totals[1]);
+    }
+    Some(totals)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::filesystem::{parse_filesystem, test_filesystem};
+
+    fn names(directories: &[&Directory]) -> Vec<String> {
+        directories.iter().map(|directory| directory.name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_default_planner() {
+        let filesystem = test_filesystem();
+        let planner = CleanupPlanner::default();
+        assert_eq!(planner.space_to_free(&filesystem), 8381165);
+        assert_eq!(planner.smallest_directory(&filesystem).unwrap().size, 24933642);
+        assert_eq!(names(&planner.candidates(&filesystem)), vec!["d", "root"]);
+        assert_eq!(names(&planner.minimum_set(&filesystem).unwrap()), vec!["d"]);
+    }
+
+    #[test]
+    fn test_minimum_set() {
+        let contents = "$ cd /\n$ ls\ndir a\ndir b\ndir c\n$ cd a\n$ ls\n40 x\n$ cd ../b\n$ ls\n35 y\ndir z\n$ cd z\n$ ls\n30 z\n$ cd /c\n$ ls\n10 w";
+        let filesystem = parse_filesystem(contents);
+
+        // 115 used, 35 free
+        let planner = CleanupPlanner::new(150, 100);
+        assert_eq!(planner.space_to_free(&filesystem), 65);
+        assert_eq!(names(&planner.candidates(&filesystem)), vec!["b", "root"]);
+        assert_eq!(names(&planner.minimum_set(&filesystem).unwrap()), vec!["b"]);
+
+        let planner = CleanupPlanner::new(200, 100);
+        assert_eq!(planner.space_to_free(&filesystem), 15);
+        assert_eq!(names(&planner.minimum_set(&filesystem).unwrap()), vec!["z"]);
+
+        let planner = CleanupPlanner::new(115, 90);
+        assert_eq!(names(&planner.minimum_set(&filesystem).unwrap()), vec!["b", "a"]);
+
+        // a + z (70), b + c (75) and a + b (105) are all enough, the smallest one is picked
+        let planner = CleanupPlanner::new(115, 70);
+        assert_eq!(names(&planner.minimum_set(&filesystem).unwrap()), vec!["a", "z"]);
+
+        let planner = CleanupPlanner::new(115, 115);
+        assert_eq!(names(&planner.minimum_set(&filesystem).unwrap()), vec!["b", "a", "c"]);
+
+        let planner = CleanupPlanner::new(115, 116);
+        assert!(planner.minimum_set(&filesystem).is_none());
+
+        let planner = CleanupPlanner::new(115, 0);
+        assert!(planner.minimum_set(&filesystem).unwrap().is_empty());
+    }
+
+    // Thousands of directories with distinct sizes, where the exact search runs out of states
+    fn large_filesystem() -> FileSystem {
+        let mut arena = vec![Directory::new(0, Arc::from("root"), None)];
+        let mut seed: u64 = 0x2545F4914F6CDD1D;
+        for idx in 1..3000 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            // Shallow parents keep the tree wide like a real one
+            let parent = (seed % idx.min(300) as u64) as usize;
+            let mut directory = Directory::new(idx, Arc::from(format!("d{}", idx).as_str()), Some(parent));
+            directory.files.insert(Arc::from("file"), 1 + (seed >> 20) as usize % 1_000_000);
+            arena[parent].children.push(idx);
+            arena.push(directory);
+        }
+        for idx in (0..arena.len()).rev() {
+            let files_size: usize = arena[idx].files.values().sum();
+            let children_size: usize = arena[idx].children.iter().map(|child| arena[*child].size).sum();
+            arena[idx].size = files_size + children_size;
+        }
+        FileSystem { arena }
+    }
+
+    #[test]
+    fn test_minimum_set_large_tree() {
+        let filesystem = large_filesystem();
+        let used = filesystem.root().size;
+        let planner = CleanupPlanner::new(used, used * 95 / 100);
+        let needed = planner.space_to_free(&filesystem);
+
+        let directories = planner.minimum_set(&filesystem).unwrap();
+        let fewest = best_selections(&filesystem, 0, 10).iter()
+            .position(|best| best.as_ref().map(|(size, _)| *size >= needed).unwrap_or(false))
+            .unwrap();
+        assert!(fewest > 1);
+        assert!(all_selections(&filesystem, 0, &Bounds::new(&filesystem, fewest, needed)).is_none());
+        assert_eq!(directories.len(), fewest);
+        assert!(directories.iter().map(|directory| directory.size).sum::<usize>() >= needed);
+        for directory in &directories {
+            let path = filesystem.path(directory.idx);
+            assert!(directories.iter().all(|other| other.idx == directory.idx || !filesystem.path(other.idx).starts_with(&format!("{}/", path))));
+        }
+    }
+}
@@ -1,19 +1,21 @@
 use std::collections::HashMap;
 
+use crate::interner::Name;
+
 #[derive(Clone)]
-pub struct Directory<'a> {
+pub struct Directory {
     pub idx: usize,
     pub size: usize,
-    pub name: &'a str,
+    pub name: Name,
     pub parent: Option<usize>,
     pub children: Vec<usize>,
-    pub files: HashMap<String, usize>
+    pub files: HashMap<Name, usize>
 }
 
 
 
-impl Directory<'_> {
-    pub fn new<'a>(idx: usize, name: &'a str, parent: Option<usize>) -> Directory<'a> {
+impl Directory {
+    pub fn new(idx: usize, name: Name, parent: Option<usize>) -> Directory {
         Directory {
             idx,
             size: 0,
@@ -25,4 +27,3 @@ impl Directory<'_> {
     }    
     
 }
-
@@ -0,0 +1,257 @@
+use std::collections::VecDeque;
+
+use crate::{directory::Directory, parser::Parser};
+
+pub enum Entry<'f> {
+    Directory(&'f Directory),
+    File {
+        parent: &'f Directory,
+        name: &'f str,
+        size: usize,
+    },
+}
+
+impl Entry<'_> {
+    pub fn name(&self) -> &str {
+        match self {
+            Entry::Directory(directory) => &directory.name,
+            Entry::File { name, .. } => name,
+        }
+    }
+}
+
+// Read-only view over the directory arena built by `Parser`. It owns all of its
+// names, so it outlives the parsed input and can be cloned or sent to other threads.
+#[derive(Clone)]
+pub struct FileSystem {
+    pub arena: Vec<Directory>,
+}
+
+impl From<Parser<'_>> for FileSystem {
+    fn from(parser: Parser<'_>) -> FileSystem {
+        FileSystem { arena: parser.arena }
+    }
+}
+
+impl FileSystem {
+    pub fn root(&self) -> &Directory {
+        &self.arena[0]
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&Directory> {
+        self.arena.get(idx)
+    }
+
+    // Looks up a directory by path, relative paths are resolved from the root
+    pub fn lookup(&self, path: &str) -> Option<&Directory> {
+        let mut cursor = self.root();
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            cursor = match segment {
+                "." => cursor,
+                ".." => cursor.parent.map(|parent| &self.arena[parent]).unwrap_or(cursor),
+                _ => cursor.children.iter()
+                    .map(|child| &self.arena[*child])
+                    .find(|child| &*child.name == segment)?,
+            };
+        }
+        Some(cursor)
+    }
+
+    // Full path of a directory, `/` for the root
+    pub fn path(&self, idx: usize) -> String {
+        let mut names = vec![];
+        let mut cursor = &self.arena[idx];
+        while let Some(parent) = cursor.parent {
+            names.push(&*cursor.name);
+            cursor = &self.arena[parent];
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    pub fn file_path(&self, parent: usize, name: &str) -> String {
+        let path = self.path(parent);
+        if path.ends_with('/') {
+            format!("{}{}", path, name)
+        } else {
+            format!("{}/{}", path, name)
+        }
+    }
+
+    // Entries of a directory, sorted by name like the puzzle statement
+    pub fn entries(&self, idx: usize) -> Vec<Entry<'_>> {
+        let directory = &self.arena[idx];
+        let mut entries: Vec<Entry<'_>> = directory.children.iter()
+            .map(|child| Entry::Directory(&self.arena[*child]))
+            .chain(directory.files.iter().map(|(name, size)| Entry::File {
+                parent: directory,
+                name,
+                size: *size,
+            }))
+            .collect();
+        entries.sort_by(|a, b| a.name().cmp(b.name()));
+        entries
+    }
+
+    // Every entry under `idx` (itself included) with its depth relative to `idx`
+    pub fn depth_first(&self, idx: usize) -> DepthFirst<'_> {
+        DepthFirst {
+            filesystem: self,
+            stack: vec![(0, Entry::Directory(&self.arena[idx]))],
+        }
+    }
+
+    pub fn breadth_first(&self, idx: usize) -> BreadthFirst<'_> {
+        BreadthFirst {
+            filesystem: self,
+            queue: VecDeque::from([(0, Entry::Directory(&self.arena[idx]))]),
+        }
+    }
+
+    // Full path and size of every file under `idx`
+    pub fn files(&self, idx: usize) -> Vec<(String, usize)> {
+        self.depth_first(idx)
+            .filter_map(|(_, entry)| match entry {
+                Entry::File { parent, name, size } => Some((self.file_path(parent.idx, name), size)),
+                Entry::Directory(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn count_files(&self, idx: usize) -> usize {
+        self.depth_first(idx)
+            .filter(|(_, entry)| matches!(entry, Entry::File { .. }))
+            .count()
+    }
+
+    // Number of directories under `idx`, not counting `idx` itself
+    pub fn count_directories(&self, idx: usize) -> usize {
+        self.depth_first(idx)
+            .filter(|(_, entry)| matches!(entry, Entry::Directory(_)))
+            .count() - 1
+    }
+
+    pub fn find_directories<F>(&self, predicate: F) -> Vec<&Directory>
+    where F: Fn(&Directory) -> bool {
+        self.arena.iter().filter(|directory| predicate(directory)).collect()
+    }
+}
+
+pub struct DepthFirst<'f> {
+    filesystem: &'f FileSystem,
+    stack: Vec<(usize, Entry<'f>)>,
+}
+
+impl<'f> Iterator for DepthFirst<'f> {
+    type Item = (usize, Entry<'f>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, entry) = self.stack.pop()?;
+        if let Entry::Directory(directory) = entry {
+            // Pushed in reverse so entries are visited in name order
+            for child in self.filesystem.entries(directory.idx).into_iter().rev() {
+                self.stack.push((depth + 1, child));
+            }
+        }
+        Some((depth, entry))
+    }
+}
+
+pub struct BreadthFirst<'f> {
+    filesystem: &'f FileSystem,
+    queue: VecDeque<(usize, Entry<'f>)>,
+}
+
+impl<'f> Iterator for BreadthFirst<'f> {
+    type Item = (usize, Entry<'f>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, entry) = self.queue.pop_front()?;
+        if let Entry::Directory(directory) = entry {
+            for child in self.filesystem.entries(directory.idx) {
+                self.queue.push_back((depth + 1, child));
+            }
+        }
+        Some((depth, entry))
+    }
+}
+
+// Builds the tree described by a terminal session, for tests that need their own layout
+#[cfg(test)]
+pub fn parse_filesystem(contents: &str) -> FileSystem {
+    let mut parser = Parser::new(contents);
+    parser.parse().unwrap();
+    FileSystem::from(parser)
+}
+
+// Example from the puzzle statement, shared by the tests of every module
+#[cfg(test)]
+pub fn test_filesystem() -> FileSystem {
+    parse_filesystem(&crate::utils::load_file("data/test.txt").unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_and_path() {
+        let filesystem = test_filesystem();
+        let e = filesystem.lookup("/a/e").unwrap();
+        assert_eq!(e.size, 584);
+        assert_eq!(filesystem.path(e.idx), "/a/e");
+        assert_eq!(filesystem.path(0), "/");
+        assert_eq!(&*filesystem.lookup("a/e/..").unwrap().name, "a");
+        assert!(filesystem.lookup("/a/missing").is_none());
+    }
+
+    #[test]
+    fn test_depth_first() {
+        let filesystem = test_filesystem();
+        let names: Vec<(usize, String)> = filesystem.depth_first(0)
+            .map(|(depth, entry)| (depth, entry.name().to_string()))
+            .collect();
+        let expected = [
+            (0, "root"), (1, "a"), (2, "e"), (3, "i"), (2, "f"), (2, "g"), (2, "h.lst"),
+            (1, "b.txt"), (1, "c.dat"), (1, "d"), (2, "d.ext"), (2, "d.log"), (2, "j"), (2, "k"),
+        ];
+        assert_eq!(names, expected.map(|(depth, name)| (depth, name.to_string())));
+    }
+
+    #[test]
+    fn test_breadth_first() {
+        let filesystem = test_filesystem();
+        let names: Vec<String> = filesystem.breadth_first(0)
+            .map(|(_, entry)| entry.name().to_string())
+            .collect();
+        assert_eq!(names[..5], ["root", "a", "b.txt", "c.dat", "d"]);
+        assert_eq!(names.last().unwrap(), "i");
+    }
+
+    // Returning the tree from a function means it does not borrow the input
+    #[test]
+    fn test_owned_filesystem() {
+        let filesystem = test_filesystem();
+        let copy = filesystem.clone();
+        assert!(std::sync::Arc::ptr_eq(&filesystem.arena[1].name, &copy.arena[1].name));
+
+        let size = std::thread::spawn(move || copy.lookup("/a/e").unwrap().size).join().unwrap();
+        assert_eq!(size, 584);
+        assert_eq!(filesystem.root().size, 48381165);
+    }
+
+    #[test]
+    fn test_files_and_counts() {
+        let filesystem = test_filesystem();
+        let a = filesystem.lookup("/a").unwrap().idx;
+        assert_eq!(filesystem.files(a), vec![
+            ("/a/e/i".to_string(), 584),
+            ("/a/f".to_string(), 29116),
+            ("/a/g".to_string(), 2557),
+            ("/a/h.lst".to_string(), 62596),
+        ]);
+        assert_eq!(filesystem.count_files(0), 10);
+        assert_eq!(filesystem.count_directories(0), 3);
+        assert_eq!(filesystem.count_directories(a), 1);
+    }
+}
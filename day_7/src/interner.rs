@@ -0,0 +1,38 @@
+use std::{collections::HashSet, sync::Arc};
+
+// Shared, immutable name. Cloning only bumps a reference count and it can be sent between threads.
+pub type Name = Arc<str>;
+
+// Hands out a single shared allocation per distinct name
+#[derive(Default)]
+pub struct Interner {
+    names: HashSet<Name>,
+}
+
+impl Interner {
+    pub fn intern(&mut self, name: &str) -> Name {
+        match self.names.get(name) {
+            Some(interned) => interned.clone(),
+            None => {
+                let interned: Name = Arc::from(name);
+                self.names.insert(interned.clone());
+                interned
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut interner = Interner::default();
+        let a = interner.intern("a");
+        let b = interner.intern("b");
+        assert!(Arc::ptr_eq(&a, &interner.intern("a")));
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(&*b, "b");
+    }
+}
@@ -0,0 +1,140 @@
+use std::{fs, io, path::{Path, PathBuf}};
+
+use crate::{directory::Directory, filesystem::FileSystem, interner::Interner};
+
+// Result of walking a directory on disk
+pub struct DiskImport {
+    pub filesystem: FileSystem,
+    // Entries that could not be read, e.g. because of missing permissions
+    pub errors: Vec<(PathBuf, io::Error)>,
+}
+
+struct Loader {
+    arena: Vec<Directory>,
+    interner: Interner,
+    errors: Vec<(PathBuf, io::Error)>,
+}
+
+impl DiskImport {
+    // Walks `path` recursively and builds the same arena `Parser` produces, symlinks are skipped.
+    // Only failing to read `path` itself is an error, anything below it is recorded in `errors`.
+    pub fn load(path: &Path) -> io::Result<DiskImport> {
+        let mut interner = Interner::default();
+        let root = Directory::new(0, interner.intern("root"), None);
+        let mut loader = Loader {
+            arena: vec![root],
+            interner,
+            errors: vec![],
+        };
+        let entries = fs::read_dir(path)?;
+        loader.load_entries(entries, 0, path);
+
+        // Children are always pushed after their parent, so walking backwards sizes them first
+        let mut arena = loader.arena;
+        for idx in (0..arena.len()).rev() {
+            let files_size: usize = arena[idx].files.values().sum();
+            let children_size: usize = arena[idx].children.iter().map(|child| arena[*child].size).sum();
+            arena[idx].size = files_size + children_size;
+        }
+
+        Ok(DiskImport {
+            filesystem: FileSystem { arena },
+            errors: loader.errors,
+        })
+    }
+}
+
+impl Loader {
+    fn load_entries(&mut self, entries: fs::ReadDir, idx: usize, path: &Path) {
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    self.errors.push((path.to_path_buf(), e));
+                    continue;
+                },
+            };
+            let entry_path = entry.path();
+            let metadata = match fs::symlink_metadata(&entry_path) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    self.errors.push((entry_path, e));
+                    continue;
+                },
+            };
+            let name = self.interner.intern(&entry.file_name().to_string_lossy());
+
+            if metadata.file_type().is_symlink() {
+                continue;
+            } else if metadata.is_dir() {
+                match fs::read_dir(&entry_path) {
+                    Ok(child_entries) => {
+                        let child = self.arena.len();
+                        self.arena.push(Directory::new(child, name, Some(idx)));
+                        self.arena[idx].children.push(child);
+                        self.load_entries(child_entries, child, &entry_path);
+                    },
+                    Err(e) => self.errors.push((entry_path, e)),
+                }
+            } else if metadata.is_file() {
+                self.arena[idx].files.insert(name, metadata.len() as usize);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleanup::CleanupPlanner;
+
+    fn create_test_directory(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("day_7_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("a/e")).unwrap();
+        fs::create_dir_all(root.join("d")).unwrap();
+        fs::write(root.join("b.txt"), vec![0u8; 1400]).unwrap();
+        fs::write(root.join("a/f"), vec![0u8; 291]).unwrap();
+        fs::write(root.join("a/e/i"), vec![0u8; 58]).unwrap();
+        fs::write(root.join("d/j"), vec![0u8; 4060]).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_load() {
+        let root = create_test_directory("load");
+        let import = DiskImport::load(&root).unwrap();
+        let filesystem = &import.filesystem;
+
+        assert!(import.errors.is_empty());
+        assert_eq!(filesystem.root().size, 5809);
+        assert_eq!(filesystem.lookup("/a").unwrap().size, 349);
+        assert_eq!(filesystem.lookup("/a/e").unwrap().size, 58);
+        assert_eq!(filesystem.count_files(0), 4);
+        assert_eq!(filesystem.find_directories(|directory| directory.size <= 1000).len(), 2);
+
+        let planner = CleanupPlanner::new(6000, 1000);
+        assert_eq!(&*planner.smallest_directory(filesystem).unwrap().name, "d");
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_skips_symlinks() {
+        let root = create_test_directory("symlinks");
+        std::os::unix::fs::symlink(root.join("d"), root.join("a/link")).unwrap();
+        std::os::unix::fs::symlink(root.join("b.txt"), root.join("a/link.txt")).unwrap();
+
+        let import = DiskImport::load(&root).unwrap();
+        let filesystem = &import.filesystem;
+        assert!(filesystem.lookup("/a/link").is_none());
+        assert_eq!(filesystem.lookup("/a").unwrap().size, 349);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing_directory() {
+        let root = std::env::temp_dir().join("day_7_missing_directory");
+        assert!(DiskImport::load(&root).is_err());
+    }
+}
@@ -1,38 +1,138 @@
-use std::{fs::File, io::Read, collections::VecDeque};
+use std::{env, path::Path};
+
 mod directory;
+pub mod cleanup;
+pub mod filesystem;
+pub mod interner;
+pub mod loader;
 pub mod parser;
+pub mod renderer;
+pub mod transcript;
 mod utils;
 
+use cleanup::CleanupPlanner;
+use filesystem::FileSystem;
+use loader::DiskImport;
 use parser::Parser;
 
 use crate::utils::load_file;
 
-
-
-
+// Path queries: `cargo run -- --find <path>` or `cargo run -- --dirs <path>`
+// Rendering: `cargo run -- --tree [path] [depth]` or `cargo run -- --du [path] [depth]`
+// Cleanup: `cargo run -- --cleanup <total space> <required space>`
+// Transcript: `cargo run -- --transcript` prints a session rebuilding the same tree
+// Disk import: `cargo run -- --disk <path> [total space] [required space]`
 fn main() {
-    let mut contents = load_file("data/input.txt").unwrap();
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(|arg| arg == "--disk").unwrap_or(false) {
+        let path = args.get(2).expect("Missing path");
+        let import = match DiskImport::load(Path::new(path)) {
+            Ok(import) => import,
+            Err(e) => panic!("Could not read {}: {}", path, e),
+        };
+        for (path, e) in &import.errors {
+            eprintln!("Skipped {}: {}", path.display(), e);
+        }
+        let filesystem = import.filesystem;
+        println!("Total: {}", filesystem.root().size);
+        let directories = filesystem.find_directories(|directory| directory.size <= 100000);
+        println!("At most 100000: {}", directories.iter().map(|directory| directory.size).sum::<usize>());
+        print_cleanup(&cleanup_planner(&args[3..]), &filesystem);
+        return;
+    }
+
+    let contents = load_file("data/input.txt").unwrap();
     
     let mut parser = Parser::new(&contents);
-    parser.parse();
-    let total_size = parser.get_root().unwrap().size;
-    println!("{}", total_size);
-    let directories = parser.find_directories(|directory| directory.size <= 100000);
-    let sum = directories.iter().fold(0, |acc, directory| acc + directory.size);
-    println!("{}", sum);
-    
-    let min_size_to_delete = 30000000 - (70000000 - total_size);
-    let mut smallest_candidate = usize::max_value();
-    parser.arena.iter().for_each(|directory| {
-        if directory.size >= min_size_to_delete && directory.size < smallest_candidate {
-            smallest_candidate = directory.size;
-        }
-    });
-    println!("delete: {}", smallest_candidate);
+    if let Err(e) = parser.parse() {
+        panic!("{}", e);
+    }
+
+    match args.get(1).map(|arg| arg.as_str()) {
+        None => {
+            let total_size = parser.get_root().unwrap().size;
+            println!("{}", total_size);
+            let directories = parser.find_directories(|directory| directory.size <= 100000);
+            let sum = directories.iter().fold(0, |acc, directory| acc + directory.size);
+            println!("{}", sum);
+
+            let filesystem = FileSystem::from(parser);
+            match CleanupPlanner::default().smallest_directory(&filesystem) {
+                Some(directory) => println!("delete: {}", directory.size),
+                None => println!("delete: nothing big enough"),
+            }
+        },
+        Some("--cleanup") => print_cleanup(&cleanup_planner(&args[2..]), &FileSystem::from(parser)),
+        Some(mode) => {
+            let path = args.get(2).map(|arg| arg.as_str()).unwrap_or("/");
+            let max_depth = args.get(3).map(|arg| arg.parse::<usize>().expect("Invalid depth"));
+            print_query(mode, path, max_depth, &FileSystem::from(parser));
+        },
+    }
+}
+
+// Reads optional total and required space arguments, falling back to the puzzle values
+fn cleanup_planner(args: &[String]) -> CleanupPlanner {
+    let default = CleanupPlanner::default();
+    let total_space = args.first().map(|arg| arg.parse::<usize>().expect("Invalid total space"));
+    let required_space = args.get(1).map(|arg| arg.parse::<usize>().expect("Invalid required space"));
+    CleanupPlanner::new(total_space.unwrap_or(default.total_space), required_space.unwrap_or(default.required_space))
+}
+
+fn print_cleanup(planner: &CleanupPlanner, filesystem: &FileSystem) {
+    println!("Space to free: {}", planner.space_to_free(filesystem));
+    println!("Candidates:");
+    for directory in planner.candidates(filesystem) {
+        println!("{} {}", directory.size, filesystem.path(directory.idx));
+    }
+    match planner.minimum_set(filesystem) {
+        Some(directories) => {
+            println!("Minimum set:");
+            for directory in directories {
+                println!("{} {}", directory.size, filesystem.path(directory.idx));
+            }
+        },
+        None => println!("No set of directories frees enough space"),
+    }
+}
+
+fn print_query(mode: &str, path: &str, max_depth: Option<usize>, filesystem: &FileSystem) {
+    let directory = match filesystem.lookup(path) {
+        Some(directory) => directory,
+        None => panic!("Directory not found: {}", path),
+    };
+    match mode {
+        "--find" => {
+            println!("{}: {} files, {} directories, {} total",
+                filesystem.path(directory.idx),
+                filesystem.count_files(directory.idx),
+                filesystem.count_directories(directory.idx),
+                directory.size);
+            for (file, size) in filesystem.files(directory.idx) {
+                println!("{} {}", size, file);
+            }
+        },
+        "--dirs" => {
+            for (_, entry) in filesystem.breadth_first(directory.idx) {
+                if let filesystem::Entry::Directory(dir) = entry {
+                    println!("{} {}", dir.size, filesystem.path(dir.idx));
+                }
+            }
+        },
+        "--tree" => print!("{}", renderer::render_tree(filesystem, directory.idx, max_depth)),
+        "--du" => print!("{}", renderer::render_du(filesystem, directory.idx, max_depth)),
+        "--transcript" => match transcript::emit_transcript(filesystem) {
+            Ok(transcript) => print!("{}", transcript),
+            Err(e) => panic!("{}", e),
+        },
+        _ => panic!("Unknown mode: {}", mode),
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::{fs::File, io::Read};
+
     use super::*;
     
     #[test]
@@ -42,7 +142,7 @@ mod test {
         file.read_to_string(&mut contents).unwrap();
         
         let mut parser = Parser::new(&contents);
-        parser.parse();
+        parser.parse().unwrap();
         assert_eq!(parser.arena[0].size, 48381165);
     }
 }
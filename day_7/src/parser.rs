@@ -1,6 +1,6 @@
-use std::collections::{VecDeque, HashMap};
+use std::{collections::{VecDeque, HashMap}, fmt};
 
-use crate::directory::Directory;
+use crate::{directory::Directory, interner::Interner};
 
 #[derive(Debug, PartialEq)]
 pub enum LineType {
@@ -9,19 +9,35 @@ pub enum LineType {
     Directory,
 }
 
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    DirectoryNotFound(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::DirectoryNotFound(path) => write!(f, "Directory not found: {}", path),
+        }
+    }
+}
+
+// Only the input being parsed is borrowed, the arena owns its names
 pub struct Parser<'a> {
     contents: VecDeque<&'a str>,
-    pub arena: Vec<Directory<'a>>,
+    pub arena: Vec<Directory>,
     cursor: usize,
+    interner: Interner,
 }
 
 
 impl Parser<'_> {
     pub fn new<'a>(contents: &'a str) -> Parser<'a> {
+        let mut interner = Interner::default();
         let directory = Directory {
             idx: 0,
             size: 0,
-            name: "root",
+            name: interner.intern("root"),
             parent: None,
             files: HashMap::new(),
             children: vec![],
@@ -29,12 +45,13 @@ impl Parser<'_> {
         let parser: Parser<'a> = Parser {
             contents: contents.lines().collect::<VecDeque<&str>>(),
             cursor: 0,
-            arena: vec![directory]
+            arena: vec![directory],
+            interner,
         };
         parser
     }
     
-    fn parse_line_type(self: &Self, line: &str) -> LineType {
+    fn parse_line_type(&self, line: &str) -> LineType {
         if line.starts_with("$") {
             LineType::Command
         } else if line.starts_with("dir") {
@@ -44,20 +61,20 @@ impl Parser<'_> {
         }
     }
     
-    fn parse_file<'a>(self: &Self, line: &'a str) -> (usize, &'a str) {
+    fn parse_file<'a>(&self, line: &'a str) -> (usize, &'a str) {
         let mut iter = line.split_whitespace();
         let value = iter.next().unwrap().parse::<usize>().unwrap();
         let name: &'a str = iter.next().unwrap();
         (value, name)
     }
     
-    fn parse_dir_name<'a>(self: &Self, line: &'a str) -> &'a str {
+    fn parse_dir_name<'a>(&self, line: &'a str) -> &'a str {
         let mut iter = line.split_whitespace();
         iter.next(); // skip the "dir" part
         iter.next().unwrap()
     }
     
-    fn process_ls<'a>(self: &mut Self) {
+    fn process_ls(&mut self) {
         self.contents.pop_front(); // remove $ ls
         let mut files = HashMap::new();
         while let Some(line) = self.contents.pop_front() {
@@ -68,14 +85,18 @@ impl Parser<'_> {
                 },
                 LineType::File => {
                     let (size, file) = self.parse_file(line);
-                    files.insert(file.to_string(), size);
+                    files.insert(self.interner.intern(file), size);
                 },
                 LineType::Directory => {
                     let name = self.parse_dir_name(line);
+                    // Listing the same directory again must not duplicate its children
+                    if self.find_among(&self.arena[self.cursor].children, |idx| &*self.arena[idx].name == name).is_some() {
+                        continue;
+                    }
                     let directory = Directory {
                         idx: self.arena.len(),
                         size: 0,
-                        name: name,
+                        name: self.interner.intern(name),
                         parent: Some(self.cursor),
                         children: vec![],
                         files: HashMap::new(),
@@ -87,47 +108,59 @@ impl Parser<'_> {
             };
             
         }
-        let value = files.values().sum();
         match self.arena.get_mut(self.cursor) {
             Some(dir) => {
-                files.iter().for_each(|(file, size)| {
-                    dir.files.insert(file.to_string(), *size);
+                // Files with the same name replace the previous listing
+                files.into_iter().for_each(|(file, size)| {
+                    dir.files.insert(file, size);
                 });
-                self.propagate_value(value);
+                self.recompute_sizes(self.cursor);
             },
             None => panic!("Directory not found"),
         }
     }
     
-    fn process_cd(self: &mut Self) {
+    fn process_cd(&mut self) -> Result<(), ParseError> {
         if let Some(line) = self.contents.pop_front() {
-            if let Some(name) = line.split_whitespace().nth(2) {
-                match name {
-                    ".." => {
-                        if let Some(parent) = self.arena[self.cursor].parent {
-                            self.cursor = parent;
-                        }
-                    },
-                    _ => {
-                        if let Some(dir) = self.find_among(
-                            &self.arena[self.cursor].children, 
-                            |idx| self.arena[idx].name == name) {
-                            self.cursor = dir.idx;
-                        }
+            if let Some(path) = line.split_whitespace().nth(2) {
+                self.cursor = self.resolve_path(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Resolves `/`, absolute and relative paths (with any number of segments) to an arena index
+    pub fn resolve_path(&self, path: &str) -> Result<usize, ParseError> {
+        let mut cursor = if path.starts_with('/') { 0 } else { self.cursor };
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            match segment {
+                "." => {},
+                ".." => {
+                    if let Some(parent) = self.arena[cursor].parent {
+                        cursor = parent;
+                    }
+                },
+                _ => {
+                    match self.find_among(
+                        &self.arena[cursor].children,
+                        |idx| &*self.arena[idx].name == segment) {
+                        Some(dir) => cursor = dir.idx,
+                        None => return Err(ParseError::DirectoryNotFound(path.to_string())),
                     }
                 }
             }
         }
+        Ok(cursor)
     }
     
-    fn process_command(self: &mut Self) {
+    fn process_command(&mut self) -> Result<(), ParseError> {
         if let Some(line) = self.contents.front() {
             match self.parse_line_type(line) {
                 LineType::Command => {
                     if let Some(command) = line.split_whitespace().nth(1) {
                         match command {
                             "ls" => self.process_ls(),
-                            "cd" => self.process_cd(),
+                            "cd" => self.process_cd()?,
                             _ => panic!("Unknown command"),
                         }
                     }
@@ -135,15 +168,17 @@ impl Parser<'_> {
                 _ => panic!("Expected command"),
             }
         }
+        Ok(())
     }
     
-    pub fn parse(self: &mut Self) {
+    pub fn parse(&mut self) -> Result<(), ParseError> {
         while !self.contents.is_empty() {
-            self.process_command();
+            self.process_command()?;
         }
+        Ok(())
     }
     
-    pub fn get_root(self: &Self) -> Option<Directory> {
+    pub fn get_root(&self) -> Option<Directory> {
         match self.arena.get(self.cursor) {
             Some(mut directory) => {
                 while let Some(parent) = directory.parent {
@@ -151,44 +186,41 @@ impl Parser<'_> {
                         directory = dir;
                     }
                 }
-                return Some(directory.clone());
+                Some(directory.clone())
             },
-            None => return None,
-        };
+            None => None,
+        }
     }
     
-    pub fn propagate_value(self: &mut Self, value: usize) {
-        let mut cursor = self.cursor;
-        match self.arena.get_mut(cursor) {
-            Some(directory) => directory.size += value,
-            None => panic!("Directory not found"),
-        }
-        while let Some(parent) = self.arena[cursor].parent {
-            match self.arena.get_mut(parent) {
-                Some(directory) => directory.size += value,
-                None => break,
-            }
-            cursor = parent;
+    // Recomputes the size of a directory from its files and children, then of all its ancestors
+    pub fn recompute_sizes(&mut self, idx: usize) {
+        let mut cursor = Some(idx);
+        while let Some(current) = cursor {
+            let files_size: usize = self.arena[current].files.values().sum();
+            let children_size: usize = self.arena[current].children.iter()
+                .map(|child| self.arena[*child].size)
+                .sum();
+            self.arena[current].size = files_size + children_size;
+            cursor = self.arena[current].parent;
         }
     }
     
-    pub fn find_directories<F>(self: &Self, predicate: F) -> Vec<&Directory> 
+    pub fn find_directories<F>(&self, predicate: F) -> Vec<&Directory> 
     where F: Fn(&Directory) -> bool {
         let mut directories = vec![];
         for directory in self.arena[..].iter() {
-            if predicate(&directory) {
+            if predicate(directory) {
                 directories.push(directory);
             }
         }
         directories
     }
 
-    fn find_among<F>(self: &Self, idxs: &[usize], predicate: F) -> Option<&Directory>
+    fn find_among<F>(&self, idxs: &[usize], predicate: F) -> Option<&Directory>
     where F: Fn(usize) -> bool {
         for idx in idxs {
-            let index = idx.clone();
             if predicate(*idx) {
-                return Some(&self.arena[index]);
+                return Some(&self.arena[*idx]);
             }
         }
         None
@@ -242,10 +274,10 @@ mod tests {
         let truncated_contents = contents.into_iter().collect::<Vec<&str>>().join("\n");
         let mut parser = Parser::new(&truncated_contents);
         parser.process_ls();
-        parser.process_cd();
-        let binding = parser.find_directories(|a| a.name == "a");
+        parser.process_cd().unwrap();
+        let binding = parser.find_directories(|a| &*a.name == "a");
         let a_dir = binding.first().unwrap();
-        assert_eq!(a_dir.name, "a");
+        assert_eq!(&*a_dir.name, "a");
         assert_eq!(a_dir.size, 0);
         
     }
@@ -254,7 +286,7 @@ mod tests {
     fn test_parse() {
         let test_data = load_file("data/test.txt").unwrap();
         let mut parser = Parser::new(&test_data);
-        parser.parse();
+        parser.parse().unwrap();
         let root = parser.get_root();
         assert_eq!(root.unwrap().size, 48381165);
     }
@@ -263,10 +295,68 @@ mod tests {
     fn test_find_directories() {
         let test_data = load_file("data/test.txt").unwrap();
         let mut parser = Parser::new(&test_data);
-        parser.parse();
+        parser.parse().unwrap();
         let directories = parser.find_directories(|dir| dir.size <= 100000);
         assert_eq!(directories.len(), 2);
     }
+
+    #[test]
+    fn test_repeated_ls() {
+        let test_data = load_file("data/test.txt").unwrap();
+        let repeated = format!("{}\n$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n$ cd a\n$ ls\ndir e\n29116 f\n2557 g\n62596 h.lst", test_data);
+        let mut parser = Parser::new(&repeated);
+        parser.parse().unwrap();
+        assert_eq!(parser.arena.len(), 4);
+        assert_eq!(parser.arena[0].children.len(), 2);
+        assert_eq!(parser.get_root().unwrap().size, 48381165);
+    }
+
+    #[test]
+    fn test_ls_replaces_files() {
+        let contents = "$ cd /\n$ ls\ndir a\n100 b.txt\n$ cd a\n$ ls\n10 c\n$ cd /\n$ ls\n200 b.txt\n$ cd a\n$ ls\n20 c\n5 d";
+        let mut parser = Parser::new(contents);
+        parser.parse().unwrap();
+        assert_eq!(parser.arena.len(), 2);
+        assert_eq!(parser.arena[1].size, 25);
+        assert_eq!(parser.arena[0].size, 225);
+    }
+
+    #[test]
+    fn test_cd_paths() {
+        let test_data = load_file("data/test.txt").unwrap();
+        let mut parser = Parser::new(&test_data);
+        parser.parse().unwrap();
+        let e = parser.find_directories(|dir| &*dir.name == "e")[0].idx;
+        let d = parser.find_directories(|dir| &*dir.name == "d")[0].idx;
+
+        parser.contents = "$ cd /\n$ cd a/e".lines().collect();
+        parser.parse().unwrap();
+        assert_eq!(parser.cursor, e);
+
+        parser.contents = "$ cd /d\n".lines().collect();
+        parser.parse().unwrap();
+        assert_eq!(parser.cursor, d);
+
+        parser.contents = "$ cd ../a/./e/..".lines().collect();
+        parser.parse().unwrap();
+        assert_eq!(parser.resolve_path("/a").unwrap(), parser.cursor);
+
+        parser.contents = "$ cd /".lines().collect();
+        parser.parse().unwrap();
+        assert_eq!(parser.cursor, 0);
+    }
+
+    #[test]
+    fn test_cd_missing_directory() {
+        let test_data = load_file("data/test.txt").unwrap();
+        let mut parser = Parser::new(&test_data);
+        parser.parse().unwrap();
+        let cursor = parser.cursor;
+
+        parser.contents = "$ cd /a/missing".lines().collect();
+        assert_eq!(parser.parse(), Err(ParseError::DirectoryNotFound("/a/missing".to_string())));
+        assert_eq!(parser.cursor, cursor);
+    }
 }
 
 
@@ -0,0 +1,107 @@
+use crate::filesystem::{Entry, FileSystem};
+
+// Renders the hierarchy like the puzzle statement, down to `max_depth` levels below `idx`
+pub fn render_tree(filesystem: &FileSystem, idx: usize, max_depth: Option<usize>) -> String {
+    let mut output = String::new();
+    for (depth, entry) in filesystem.depth_first(idx) {
+        if max_depth.map(|max| depth > max).unwrap_or(false) {
+            continue;
+        }
+        let indent = "  ".repeat(depth);
+        match entry {
+            Entry::Directory(directory) => {
+                let name = if directory.parent.is_none() { "/" } else { &directory.name };
+                output.push_str(&format!("{}- {} (dir)\n", indent, name));
+            },
+            Entry::File { name, size, .. } => {
+                output.push_str(&format!("{}- {} (file, size={})\n", indent, name, size));
+            },
+        }
+    }
+    output
+}
+
+// Lists directories with their cumulative size, largest first, like `du -h | sort -rh`
+pub fn render_du(filesystem: &FileSystem, idx: usize, max_depth: Option<usize>) -> String {
+    let mut directories: Vec<(usize, String)> = filesystem.depth_first(idx)
+        .filter(|(depth, _)| max_depth.map(|max| *depth <= max).unwrap_or(true))
+        .filter_map(|(_, entry)| match entry {
+            Entry::Directory(directory) => Some((directory.size, filesystem.path(directory.idx))),
+            Entry::File { .. } => None,
+        })
+        .collect();
+    directories.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    directories.iter()
+        .map(|(size, path)| format!("{}\t{}\n", human_size(*size), path))
+        .collect()
+}
+
+pub fn human_size(size: usize) -> String {
+    let units = ["K", "M", "G", "T"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64;
+    let mut unit = "";
+    for candidate in units {
+        value /= 1024.0;
+        unit = candidate;
+        if value < 1024.0 {
+            break;
+        }
+    }
+    if value < 10.0 {
+        format!("{:.1}{}", value, unit)
+    } else {
+        format!("{:.0}{}", value, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::test_filesystem;
+
+    const TREE: &str = "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+";
+
+    #[test]
+    fn test_render_tree() {
+        let filesystem = test_filesystem();
+        assert_eq!(render_tree(&filesystem, 0, None), TREE);
+
+        let shallow = render_tree(&filesystem, 0, Some(1));
+        assert_eq!(shallow.lines().count(), 5);
+        assert!(!shallow.contains("- e (dir)"));
+    }
+
+    #[test]
+    fn test_render_du() {
+        let filesystem = test_filesystem();
+        assert_eq!(render_du(&filesystem, 0, None), "46M\t/\n24M\t/d\n93K\t/a\n584\t/a/e\n");
+        assert_eq!(render_du(&filesystem, 0, Some(0)), "46M\t/\n");
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(584), "584");
+        assert_eq!(human_size(2048), "2.0K");
+        assert_eq!(human_size(94853), "93K");
+        assert_eq!(human_size(5 * 1024 * 1024 * 1024), "5.0G");
+    }
+}
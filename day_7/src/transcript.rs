@@ -0,0 +1,132 @@
+use std::fmt;
+
+use crate::filesystem::{Entry, FileSystem};
+
+#[derive(Debug, PartialEq)]
+pub enum TranscriptError {
+    // The parser splits lines on whitespace, so these names can't be read back
+    UnsupportedName(String),
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranscriptError::UnsupportedName(path) => write!(f, "Name can't be written to a transcript: {}", path),
+        }
+    }
+}
+
+// Emits a terminal session that `Parser::parse` turns back into the same tree
+pub fn emit_transcript(filesystem: &FileSystem) -> Result<String, TranscriptError> {
+    let mut output = String::from("$ cd /\n");
+    emit_directory(filesystem, filesystem.root().idx, &mut output)?;
+    Ok(output)
+}
+
+fn emit_directory(filesystem: &FileSystem, idx: usize, output: &mut String) -> Result<(), TranscriptError> {
+    let entries = filesystem.entries(idx);
+    output.push_str("$ ls\n");
+    for entry in &entries {
+        let name = entry.name();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(TranscriptError::UnsupportedName(filesystem.file_path(idx, name)));
+        }
+        match entry {
+            Entry::Directory(directory) => output.push_str(&format!("dir {}\n", directory.name)),
+            Entry::File { name, size, .. } => output.push_str(&format!("{} {}\n", size, name)),
+        }
+    }
+    for entry in entries {
+        if let Entry::Directory(directory) = entry {
+            output.push_str(&format!("$ cd {}\n", directory.name));
+            emit_directory(filesystem, directory.idx, output)?;
+            output.push_str("$ cd ..\n");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{directory::Directory, filesystem::{parse_filesystem, test_filesystem}, renderer::render_tree};
+
+    // Small xorshift generator so the round trip can be checked on many trees without extra crates
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, max: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % max as u64) as usize
+        }
+    }
+
+    fn random_arena(random: &mut Random, names: &[String]) -> Vec<Directory> {
+        let mut arena = vec![Directory::new(0, Arc::from("root"), None)];
+        for (i, name) in names.iter().enumerate() {
+            if name.starts_with('d') {
+                let parent = random.next(arena.len());
+                let idx = arena.len();
+                arena[parent].children.push(idx);
+                arena.push(Directory::new(idx, Arc::from(name.as_str()), Some(parent)));
+            } else {
+                let parent = random.next(arena.len());
+                arena[parent].files.insert(Arc::from(name.as_str()), 1 + random.next(100000) * (i + 1));
+            }
+        }
+        arena
+    }
+
+    #[test]
+    fn test_round_trip_example() {
+        let original = test_filesystem();
+        let transcript = emit_transcript(&original).unwrap();
+        let parsed = parse_filesystem(&transcript);
+        assert_eq!(render_tree(&parsed, 0, None), render_tree(&original, 0, None));
+        assert_eq!(parsed.root().size, 48381165);
+    }
+
+    #[test]
+    fn test_round_trip_random_trees() {
+        let mut random = Random(0x9E3779B97F4A7C15);
+        for _ in 0..50 {
+            let count = 1 + random.next(40);
+            let names: Vec<String> = (0..count)
+                .map(|i| if random.next(3) == 0 { format!("d{}", i) } else { format!("f{}.txt", i) })
+                .collect();
+            let original = FileSystem { arena: random_arena(&mut random, &names) };
+
+            let transcript = emit_transcript(&original).unwrap();
+            let parsed = parse_filesystem(&transcript);
+
+            assert_eq!(render_tree(&parsed, 0, None), render_tree(&original, 0, None));
+            for (_, entry) in original.depth_first(0) {
+                if let Entry::Directory(directory) = entry {
+                    let path = original.path(directory.idx);
+                    let expected: usize = original.files(directory.idx).iter().map(|(_, size)| size).sum();
+                    assert_eq!(parsed.lookup(&path).unwrap().size, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsupported_names() {
+        for name in ["my file.txt", "tab\tname", ""] {
+            let mut arena = vec![Directory::new(0, Arc::from("root"), None), Directory::new(1, Arc::from("a"), Some(0))];
+            arena[0].children.push(1);
+            arena[1].files.insert(Arc::from(name), 10);
+            let filesystem = FileSystem { arena };
+            assert_eq!(emit_transcript(&filesystem), Err(TranscriptError::UnsupportedName(format!("/a/{}", name))));
+        }
+
+        let mut arena = vec![Directory::new(0, Arc::from("root"), None), Directory::new(1, Arc::from("new folder"), Some(0))];
+        arena[0].children.push(1);
+        let filesystem = FileSystem { arena };
+        assert_eq!(emit_transcript(&filesystem), Err(TranscriptError::UnsupportedName("/new folder".to_string())));
+    }
+}
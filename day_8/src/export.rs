@@ -0,0 +1,161 @@
+use std::{fs, io};
+
+use crate::forest::Forest;
+
+type Color = (u8, u8, u8);
+
+const HIGHLIGHT: Color = (255, 0, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layer {
+    Heights,
+    Visibility,
+    Scenic,
+}
+
+impl Layer {
+    pub fn from_arg(arg: &str) -> Option<Layer> {
+        match arg {
+            "heights" => Some(Layer::Heights),
+            "visibility" => Some(Layer::Visibility),
+            "scenic" => Some(Layer::Scenic),
+            _ => None,
+        }
+    }
+}
+
+// Everything the exporters need to draw any layer of the forest
+pub struct ForestImage<'f> {
+    pub forest: &'f Forest,
+    pub visible: &'f [Vec<bool>],
+    pub scores: &'f [Vec<usize>],
+    // (row, column) of the tree to highlight, usually the best scenic score
+    pub best: Option<(usize, usize)>,
+}
+
+impl ForestImage<'_> {
+    fn colors(&self, layer: Layer) -> Vec<Vec<Color>> {
+        let max_score = self.scores.iter().flatten().copied().max().unwrap_or(0).max(1) as f64;
+        (0..self.forest.height()).map(|row| (0..self.forest.width()).map(|column| {
+            match layer {
+                Layer::Heights => gradient(self.forest.get(row, column) as f64 / 9.0),
+                Layer::Visibility => if self.visible[row][column] { (240, 220, 80) } else { (30, 40, 60) },
+                // Square root so the few very high scores don't flatten everything else
+                Layer::Scenic => gradient((self.scores[row][column] as f64 / max_score).sqrt()),
+            }
+        }).collect()).collect()
+    }
+
+    // Coloured cells with the tree height written in each, for 24-bit colour terminals
+    pub fn to_ansi(&self, layer: Layer) -> String {
+        let mut output = String::new();
+        for (row, colors) in self.colors(layer).iter().enumerate() {
+            for (column, color) in colors.iter().enumerate() {
+                let (r, g, b) = if self.best == Some((row, column)) { HIGHLIGHT } else { *color };
+                output.push_str(&format!("\x1b[48;2;{};{};{}m{}", r, g, b, self.forest.get(row, column)));
+            }
+            output.push_str("\x1b[0m\n");
+        }
+        output
+    }
+
+    // Binary PPM (P6) with a `scale` x `scale` square of pixels per tree
+    pub fn to_ppm(&self, layer: Layer, scale: usize) -> Vec<u8> {
+        let colors = self.colors(layer);
+        let (width, height) = (self.forest.width() * scale, self.forest.height() * scale);
+        let mut output = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for y in 0..height {
+            for x in 0..width {
+                let (row, column) = (y / scale, x / scale);
+                let (r, g, b) = if self.best == Some((row, column)) { HIGHLIGHT } else { colors[row][column] };
+                output.extend([r, g, b]);
+            }
+        }
+        output
+    }
+
+    pub fn to_svg(&self, layer: Layer, cell: usize) -> String {
+        let colors = self.colors(layer);
+        let (width, height) = (self.forest.width() * cell, self.forest.height() * cell);
+        let mut output = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            width, height, width, height);
+        for (row, line) in colors.iter().enumerate() {
+            for (column, (r, g, b)) in line.iter().enumerate() {
+                output.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{:02x}{:02x}{:02x}\"/>\n",
+                    column * cell, row * cell, cell, cell, r, g, b));
+            }
+        }
+        if let Some((row, column)) = self.best {
+            output.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#ff0000\" stroke-width=\"{}\"/>\n",
+                column * cell, row * cell, cell, cell, (cell / 4).max(1)));
+        }
+        output.push_str("</svg>\n");
+        output
+    }
+
+    pub fn write_ppm(&self, layer: Layer, scale: usize, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_ppm(layer, scale))
+    }
+
+    pub fn write_svg(&self, layer: Layer, cell: usize, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_svg(layer, cell))
+    }
+}
+
+// Dark green to light yellow for a value between 0 and 1
+fn gradient(value: f64) -> Color {
+    let value = value.clamp(0.0, 1.0);
+    let mix = |from: f64, to: f64| (from + (to - from) * value).round() as u8;
+    (mix(10.0, 250.0), mix(50.0, 240.0), mix(20.0, 120.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forest::test_forest;
+    use crate::{scenic, visibility};
+
+    fn with_image<F: Fn(&ForestImage)>(f: F) {
+        let forest = test_forest();
+        let visible = visibility::visibility_map(&forest);
+        let scores = scenic::scenic_scores(&forest);
+        let best = scenic::best_tree(&scores).map(|(position, _)| position);
+        f(&ForestImage { forest: &forest, visible: &visible, scores: &scores, best });
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        with_image(|image| {
+            let output = image.to_ansi(Layer::Heights);
+            assert_eq!(output.lines().count(), 5);
+            assert!(output.contains("\x1b[48;2;255;0;0m5"));
+            assert_eq!(output.matches("\x1b[48;2;").count(), 25);
+        });
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        with_image(|image| {
+            let output = image.to_ppm(Layer::Visibility, 2);
+            let header = b"P6\n10 10\n255\n";
+            assert_eq!(&output[..header.len()], header);
+            assert_eq!(output.len(), header.len() + 10 * 10 * 3);
+            // Best tree is at row 3, column 2, so pixel (x 4, y 6)
+            let pixel = header.len() + (6 * 10 + 4) * 3;
+            assert_eq!(&output[pixel..pixel + 3], &[255, 0, 0]);
+        });
+    }
+
+    #[test]
+    fn test_to_svg() {
+        with_image(|image| {
+            let output = image.to_svg(Layer::Scenic, 10);
+            assert!(output.starts_with("<svg"));
+            assert_eq!(output.matches("<rect").count(), 26);
+            assert!(output.contains("<rect x=\"20\" y=\"30\" width=\"10\" height=\"10\" fill=\"none\" stroke=\"#ff0000\""));
+        });
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    // Line and column are 1-based
+    InvalidHeight { line: usize, column: usize, character: char },
+    RaggedRow { line: usize, expected: usize, found: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidHeight { line, column, character } =>
+                write!(f, "Invalid tree height '{}' at line {}, column {}", character, line, column),
+            ParseError::RaggedRow { line, expected, found } =>
+                write!(f, "Line {} has {} trees, expected {}", line, found, expected),
+        }
+    }
+}
+
+// Grid of tree heights, indexed by (row, column). Rows are stored one after the other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forest {
+    trees: Vec<u32>,
+    width: usize,
+    height: usize,
+}
+
+impl Forest {
+    pub fn parse(input: &str) -> Result<Forest, ParseError> {
+        let mut trees = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(column, c)| c.to_digit(10)
+                    .ok_or(ParseError::InvalidHeight { line: i + 1, column: column + 1, character: c }))
+                .collect::<Result<Vec<u32>, ParseError>>()?;
+
+            let expected = *width.get_or_insert(row.len());
+            if row.len() != expected {
+                return Err(ParseError::RaggedRow { line: i + 1, expected, found: row.len() });
+            }
+            trees.extend(row);
+            height += 1;
+        }
+        Ok(Forest {
+            trees,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn row(&self, row: usize) -> &[u32] {
+        &self.trees[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> u32 {
+        self.trees[row * self.width + column]
+    }
+}
+
+// Example from the puzzle statement, shared by the tests of every module
+#[cfg(test)]
+pub fn test_forest() -> Forest {
+    let input = crate::load_file("data/test.txt").unwrap();
+    Forest::parse(&input).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let forest = Forest::parse("123\n456\n").unwrap();
+        assert_eq!((forest.height(), forest.width()), (2, 3));
+        assert_eq!(forest.get(1, 0), 4);
+        assert_eq!(forest.row(1), &[4, 5, 6]);
+    }
+
+    #[test]
+    fn test_parse_ragged_row() {
+        assert_eq!(Forest::parse("123\n45\n678"), Err(ParseError::RaggedRow { line: 2, expected: 3, found: 2 }));
+    }
+
+    #[test]
+    fn test_parse_invalid_height() {
+        assert_eq!(Forest::parse("123\n4x6"), Err(ParseError::InvalidHeight { line: 2, column: 2, character: 'x' }));
+    }
+}
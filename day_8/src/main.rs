@@ -1,33 +1,81 @@
-use std::{fs::File, io::Read};
+use std::{env, fs::File, io::Read};
 
+mod export;
+mod forest;
+mod scenic;
+mod viewing;
+mod visibility;
+
+use export::{ForestImage, Layer};
+use forest::Forest;
+use viewing::ViewingRules;
+
+// Export: `cargo run -- --ansi <heights|visibility|scenic>`
+//         `cargo run -- --ppm <heights|visibility|scenic> <file>`
+//         `cargo run -- --svg <heights|visibility|scenic> <file>`
+// Variants: `cargo run -- --rules <axes|diagonals> [radius or -] [observer height]`
 fn main() {
     let input = load_file("data/input.txt").unwrap();
-    let forest = parse_forest(&input);
-    let mut visible_trees = 0;
-    for x in 0..forest.len() {
-        for y in 0..forest[x].len() {
-            let tree = (x as u32, y as u32);
-            if is_visible_from_north(forest.clone(), tree) ||
-                is_visible_from_south(forest.clone(), tree) ||
-                is_visible_from_east(forest.clone(), tree) ||
-                is_visible_from_west(forest.clone(), tree) {
-                    visible_trees += 1;
-                }
-        }
-    }
+    let forest = match Forest::parse(&input) {
+        Ok(forest) => forest,
+        Err(e) => panic!("{}", e),
+    };
+    let visible = visibility::visibility_map(&forest);
+    let visible_trees = visibility::count_visible(&visible);
     println!("Visible trees: {}", visible_trees);
 
-    let mut max_los = 0;
-    for x in 0..forest.len() {
-        for y in 0..forest[x].len() {
-            let tree = (x as u32, y as u32);
-            let los = calculate_los(forest.clone(), tree);
-            if los > max_los {
-                max_los = los;
+    let scores = scenic::scenic_scores(&forest);
+    let best = scenic::best_tree(&scores);
+    match best {
+        Some(((row, column), score)) => println!("Max LOS: {} (row {}, column {})", score, row, column),
+        None => println!("Max LOS: empty forest"),
+    }
+
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("--rules") => {
+            let mut rules = match args.get(2).map(|arg| arg.as_str()) {
+                Some("diagonals") => ViewingRules::with_diagonals(),
+                Some("axes") | None => ViewingRules::default(),
+                Some(other) => panic!("Unknown rules: {}", other),
+            };
+            if let Some(radius) = args.get(3).filter(|arg| *arg != "-") {
+                rules = rules.radius(radius.parse::<usize>().expect("Invalid radius"));
             }
-        }
+            if let Some(offset) = args.get(4) {
+                rules = rules.observer_height(offset.parse::<i64>().expect("Invalid observer height"));
+            }
+            let visible_trees = visibility::count_visible(&rules.visibility_map(&forest));
+            println!("Visible trees with {:?}: {}", rules, visible_trees);
+            match scenic::best_tree(&rules.scenic_scores(&forest)) {
+                Some(((row, column), score)) => println!("Max LOS with {:?}: {} (row {}, column {})", rules, score, row, column),
+                None => println!("Max LOS: empty forest"),
+            }
+        },
+        Some(mode) => {
+            let layer = args.get(2).expect("Missing layer");
+            let layer = Layer::from_arg(layer).unwrap_or_else(|| panic!("Unknown layer: {}", layer));
+            let image = ForestImage {
+                forest: &forest,
+                visible: &visible,
+                scores: &scores,
+                best: best.map(|(position, _)| position),
+            };
+            let result = match (mode, args.get(3)) {
+                ("--ansi", _) => {
+                    print!("{}", image.to_ansi(layer));
+                    Ok(())
+                },
+                ("--ppm", Some(path)) => image.write_ppm(layer, 4, path),
+                ("--svg", Some(path)) => image.write_svg(layer, 8, path),
+                _ => panic!("Unknown export: {}", mode),
+            };
+            if let Err(e) = result {
+                panic!("Could not export: {}", e);
+            }
+        },
+        None => {},
     }
-    println!("Max LOS: {}", max_los);
 }
 
 fn load_file(path: &str) -> Result<String, std::io::Error> {
@@ -36,139 +84,3 @@ fn load_file(path: &str) -> Result<String, std::io::Error> {
     file.read_to_string(&mut contents)?;
     Ok(contents)
 }
-
-fn parse_forest(input: &str) -> Vec<Vec<u32>> {
-    input
-        .lines()
-        .map(|column| {
-            column
-            .chars()
-            .map(|c| c.to_digit(10)
-                .unwrap_or(0))
-            .collect()
-        })
-        .collect()
-}
-
-fn is_visible_from_north(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> bool {
-    let (x, y) = tree;
-    let height = forest[x as usize][y as usize];
-    for i in 0..x {
-        if forest[i as usize][y as usize] >= height {
-            return false;
-        }
-    }
-    true
-}
-
-fn is_visible_from_south(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> bool {
-    let (x, y) = tree;
-    let height = forest[x as usize][y as usize];
-    for i in x+1..forest[x as usize].len() as u32 {
-        if forest[i as usize][y as usize] >= height {
-            return false;
-        }
-    }
-    true
-}
-
-fn is_visible_from_east(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> bool {
-    let (x, y) = tree;
-    let height = forest[x as usize][y as usize];
-    for i in y+1..forest.len() as u32 {
-        if forest[x as usize][i as usize] >= height {
-            return false;
-        }
-    }
-    true
-}
-
-fn is_visible_from_west(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> bool {
-    let (x, y) = tree;
-    let height = forest[x as usize][y as usize];
-    for i in 0..y {
-        if forest[x as usize][i as usize] >= height {
-            return false;
-        }
-    }
-    true
-}
-
-fn los_north(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> u32 {
-    let (x, y) = tree;
-    if x == 0 {
-        return 0;
-    }
-    let mut los = 0;
-    let height: u32 = forest[x as usize][y as usize];
-    let range: Vec<u32> = (0..x).rev().collect();
-    for i in range {
-        los += 1;
-        let current = forest[i as usize][y as usize];
-        if current >= height {
-            return los;
-        }
-    }
-    los
-}
-
-fn los_south(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> u32 {
-    let (x, y) = tree;
-    if x == forest.len() as u32 - 1 {
-        return 0;
-    }
-    let mut los = 0;
-    let height = forest[x as usize][y as usize];
-    let range: Vec<u32> = (x+1..forest.len() as u32).collect();
-    for i in range {
-        los += 1;
-        let current = forest[i as usize][y as usize];
-        if current >= height {
-            return los;
-        }
-    }
-    los
-}
-
-fn los_east(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> u32 {
-    let (x, y) = tree;
-    if y == forest.len() as u32 - 1 {
-        return 0;
-    }
-    let mut los = 0;
-    let height = forest[x as usize][y as usize];
-    let range: Vec<u32> = (y+1..forest.len() as u32).collect();
-    for i in range {
-        los += 1;
-        let current = forest[x as usize][i as usize];
-        if current >= height {
-            return los;
-        }
-    }
-    los
-}
-
-fn los_west(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> u32 {
-    let (x, y) = tree;
-    if y == 0 {
-        return 0;
-    }
-    let mut los = 0;
-    let height = forest[x as usize][y as usize];
-    let range: Vec<u32> = (0..y).rev().collect();
-    for i in range {
-        los += 1;
-        let current = forest[x as usize][i as usize];
-        if current >= height {
-            return los;
-        }
-    }
-    los
-}
-
-fn calculate_los(forest: Vec<Vec<u32>>, tree: (u32, u32)) -> u32 {
-    los_north(forest.clone(), tree) *
-    los_south(forest.clone(), tree) *
-    los_east(forest.clone(), tree) *
-    los_west(forest.clone(), tree)
-}
\ No newline at end of file
@@ -0,0 +1,101 @@
+use crate::forest::Forest;
+
+// Scenic score of every tree. Viewing distances in each direction come from a
+// monotonic stack per row and column, so each line is processed in linear time.
+pub fn scenic_scores(forest: &Forest) -> Vec<Vec<usize>> {
+    let (height, width) = (forest.height(), forest.width());
+    let mut scores = vec![vec![1; width]; height];
+
+    for row in 0..height {
+        apply_distances(&mut scores, forest.row(row), |column| (row, column));
+    }
+    for column in 0..width {
+        let line: Vec<u32> = (0..height).map(|row| forest.get(row, column)).collect();
+        apply_distances(&mut scores, &line, |row| (row, column));
+    }
+    scores
+}
+
+// Coordinates (row, column) and score of the tree with the best scenic score
+pub fn best_tree(scores: &[Vec<usize>]) -> Option<((usize, usize), usize)> {
+    scores.iter()
+        .enumerate()
+        .flat_map(|(row, line)| line.iter().enumerate().map(move |(column, score)| ((row, column), *score)))
+        .max_by_key(|(_, score)| *score)
+}
+
+// Multiplies the scores along a line by the viewing distances looking both ways
+fn apply_distances<F>(scores: &mut [Vec<usize>], line: &[u32], position: F)
+where F: Fn(usize) -> (usize, usize) {
+    let backward = viewing_distances(line);
+    let reversed: Vec<u32> = line.iter().rev().copied().collect();
+    let forward = viewing_distances(&reversed);
+    for i in 0..line.len() {
+        let (row, column) = position(i);
+        scores[row][column] *= backward[i] * forward[line.len() - 1 - i];
+    }
+}
+
+// Number of trees each tree can see looking towards the start of the line.
+// The stack keeps the indices of trees not yet blocked by a taller or equal tree.
+fn viewing_distances(line: &[u32]) -> Vec<usize> {
+    let mut distances = vec![0; line.len()];
+    let mut stack: Vec<usize> = vec![];
+    for (i, height) in line.iter().enumerate() {
+        while let Some(top) = stack.last() {
+            if line[*top] < *height {
+                stack.pop();
+            } else {
+                break;
+            }
+        }
+        distances[i] = match stack.last() {
+            Some(blocking) => i - blocking,
+            None => i,
+        };
+        stack.push(i);
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{forest::test_forest, viewing::ViewingRules};
+
+    #[test]
+    fn test_viewing_distances() {
+        assert_eq!(viewing_distances(&[3, 0, 3, 7, 3]), vec![0, 1, 2, 3, 1]);
+        assert_eq!(viewing_distances(&[3, 3, 5, 4, 9]), vec![0, 1, 2, 1, 4]);
+    }
+
+    #[test]
+    fn test_scenic_scores() {
+        let forest = test_forest();
+        let scores = scenic_scores(&forest);
+        assert_eq!(scores[1][2], 4);
+        assert_eq!(scores[3][2], 8);
+        assert_eq!(scores[0][0], 0);
+        assert_eq!(best_tree(&scores), Some(((3, 2), 8)));
+    }
+
+    #[test]
+    fn test_wide_forest() {
+        let forest = Forest::parse("3037312\n2551290\n6533213\n1234567").unwrap();
+        let scores = scenic_scores(&forest);
+        assert_eq!(scores, ViewingRules::default().scenic_scores(&forest));
+        assert_eq!(scores[1][2], 6);
+        assert_eq!(scores[2][3], 6);
+        assert_eq!(best_tree(&scores), Some(((1, 5), 10)));
+    }
+
+    #[test]
+    fn test_tall_forest() {
+        let forest = Forest::parse("3037\n2551\n6533\n3354\n3539\n1296\n0134").unwrap();
+        let scores = scenic_scores(&forest);
+        assert_eq!(scores, ViewingRules::default().scenic_scores(&forest));
+        assert_eq!(scores[3][2], 8);
+        assert_eq!(scores[4][1], 8);
+        assert_eq!(best_tree(&scores), Some(((5, 2), 10)));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::forest::Forest;
+
+const AXES: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const DIAGONALS: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+// Parameters for the slower, ray-walking variants of visibility and scenic scores.
+// A tree blocks the view when it is at least as tall as the viewed tree plus `observer_height`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewingRules {
+    // (row, column) steps, each one is walked until the edge of the forest
+    pub directions: Vec<(isize, isize)>,
+    // How many trees deep one can see, unlimited if None
+    pub radius: Option<usize>,
+    pub observer_height: i64,
+}
+
+impl Default for ViewingRules {
+    // Rules from the puzzle statement
+    fn default() -> ViewingRules {
+        ViewingRules::new(AXES.to_vec())
+    }
+}
+
+impl ViewingRules {
+    pub fn new(directions: Vec<(isize, isize)>) -> ViewingRules {
+        ViewingRules {
+            directions,
+            radius: None,
+            observer_height: 0,
+        }
+    }
+
+    pub fn with_diagonals() -> ViewingRules {
+        ViewingRules::new(AXES.iter().chain(DIAGONALS.iter()).copied().collect())
+    }
+
+    pub fn radius(mut self, radius: usize) -> ViewingRules {
+        self.radius = Some(radius);
+        self
+    }
+
+    pub fn observer_height(mut self, observer_height: i64) -> ViewingRules {
+        self.observer_height = observer_height;
+        self
+    }
+
+    // Trees from (row, column), excluded, to the edge of the forest in one direction
+    fn ray(&self, forest: &Forest, row: usize, column: usize, (dr, dc): (isize, isize)) -> Vec<u32> {
+        let mut trees = vec![];
+        let (mut r, mut c) = (row as isize + dr, column as isize + dc);
+        while r >= 0 && c >= 0 && (r as usize) < forest.height() && (c as usize) < forest.width() {
+            trees.push(forest.get(r as usize, c as usize));
+            r += dr;
+            c += dc;
+        }
+        trees
+    }
+
+    fn eye_level(&self, forest: &Forest, row: usize, column: usize) -> i64 {
+        forest.get(row, column) as i64 + self.observer_height
+    }
+
+    // Seen from outside, so the tree has to be within `radius` of the edge it is looked at from
+    pub fn is_visible(&self, forest: &Forest, row: usize, column: usize) -> bool {
+        let eye_level = self.eye_level(forest, row, column);
+        self.directions.iter().any(|direction| {
+            let ray = self.ray(forest, row, column, *direction);
+            self.radius.map(|radius| ray.len() < radius).unwrap_or(true)
+                && ray.iter().all(|tree| (*tree as i64) < eye_level)
+        })
+    }
+
+    pub fn viewing_distance(&self, forest: &Forest, row: usize, column: usize, direction: (isize, isize)) -> usize {
+        let eye_level = self.eye_level(forest, row, column);
+        let ray = self.ray(forest, row, column, direction);
+        let limit = self.radius.unwrap_or(ray.len()).min(ray.len());
+        match ray[..limit].iter().position(|tree| *tree as i64 >= eye_level) {
+            Some(blocking) => blocking + 1,
+            None => limit,
+        }
+    }
+
+    pub fn scenic_score(&self, forest: &Forest, row: usize, column: usize) -> usize {
+        self.directions.iter()
+            .map(|direction| self.viewing_distance(forest, row, column, *direction))
+            .product()
+    }
+
+    pub fn visibility_map(&self, forest: &Forest) -> Vec<Vec<bool>> {
+        (0..forest.height())
+            .map(|row| (0..forest.width()).map(|column| self.is_visible(forest, row, column)).collect())
+            .collect()
+    }
+
+    pub fn scenic_scores(&self, forest: &Forest) -> Vec<Vec<usize>> {
+        (0..forest.height())
+            .map(|row| (0..forest.width()).map(|column| self.scenic_score(forest, row, column)).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forest::test_forest;
+    use crate::{scenic, visibility};
+
+    #[test]
+    fn test_default_rules_match_puzzle() {
+        let forest = test_forest();
+        let rules = ViewingRules::default();
+        assert_eq!(rules.visibility_map(&forest), visibility::visibility_map(&forest));
+        assert_eq!(rules.scenic_scores(&forest), scenic::scenic_scores(&forest));
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let forest = test_forest();
+        let rules = ViewingRules::with_diagonals();
+        // Row 3, column 3 (height 4) is hidden along the axes but visible along the north-east diagonal
+        assert!(!visibility::visibility_map(&forest)[3][3]);
+        assert!(rules.is_visible(&forest, 3, 3));
+        // Row 3, column 2 (height 5): axes give 2 * 2 * 1 * 2, diagonals 1 * 2 * 1 * 1
+        assert_eq!(rules.scenic_score(&forest, 3, 2), 16);
+        assert_eq!(rules.viewing_distance(&forest, 1, 1, (1, 1)), 3);
+    }
+
+    #[test]
+    fn test_radius() {
+        let forest = test_forest();
+        let rules = ViewingRules::default().radius(1);
+        assert_eq!(visibility::count_visible(&rules.visibility_map(&forest)), 16);
+        assert_eq!(rules.scenic_score(&forest, 3, 2), 1);
+        assert_eq!(ViewingRules::default().radius(2).scenic_score(&forest, 3, 2), 8);
+    }
+
+    #[test]
+    fn test_observer_height() {
+        let forest = test_forest();
+        let rules = ViewingRules::default().observer_height(1);
+        // Trees of the same height no longer block the view
+        assert!(rules.is_visible(&forest, 2, 2));
+        assert_eq!(rules.viewing_distance(&forest, 1, 1, (0, 1)), 3);
+        assert_eq!(rules.viewing_distance(&forest, 1, 1, (0, 1)), ViewingRules::default().viewing_distance(&forest, 1, 1, (0, 1)) + 2);
+    }
+}
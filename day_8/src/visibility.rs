@@ -0,0 +1,81 @@
+use crate::forest::Forest;
+
+// Marks every tree visible from outside the forest. Each row and column is swept once
+// from both ends while keeping the tallest tree seen so far, so the whole pass is O(n²).
+pub fn visibility_map(forest: &Forest) -> Vec<Vec<bool>> {
+    let (height, width) = (forest.height(), forest.width());
+    let mut visible = vec![vec![false; width]; height];
+
+    for row in 0..height {
+        sweep(forest, &mut visible, (0..width).map(|column| (row, column)));
+        sweep(forest, &mut visible, (0..width).rev().map(|column| (row, column)));
+    }
+    for column in 0..width {
+        sweep(forest, &mut visible, (0..height).map(|row| (row, column)));
+        sweep(forest, &mut visible, (0..height).rev().map(|row| (row, column)));
+    }
+    visible
+}
+
+pub fn count_visible(visible: &[Vec<bool>]) -> usize {
+    visible.iter().flatten().filter(|tree| **tree).count()
+}
+
+fn sweep<I>(forest: &Forest, visible: &mut [Vec<bool>], line: I)
+where I: Iterator<Item = (usize, usize)> {
+    // Height 0 trees on the edge are still visible, so start below any height
+    let mut tallest: i64 = -1;
+    for (row, column) in line {
+        let tree = forest.get(row, column) as i64;
+        if tree > tallest {
+            visible[row][column] = true;
+            tallest = tree;
+        }
+        if tallest == 9 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{forest::test_forest, viewing::ViewingRules};
+
+    #[test]
+    fn test_count_visible() {
+        let forest = test_forest();
+        assert_eq!(count_visible(&visibility_map(&forest)), 21);
+    }
+
+    #[test]
+    fn test_visibility_map() {
+        let forest = test_forest();
+        let visible = visibility_map(&forest);
+        assert!(visible[1][1]);
+        assert!(visible[1][2]);
+        assert!(!visible[1][3]);
+        assert!(!visible[2][2]);
+        assert!(visible[3][2]);
+    }
+
+    #[test]
+    fn test_wide_forest() {
+        let forest = Forest::parse("3037312\n2551290\n6533213").unwrap();
+        assert_eq!((forest.height(), forest.width()), (3, 7));
+        let visible = visibility_map(&forest);
+        assert_eq!(visible, ViewingRules::default().visibility_map(&forest));
+        // The 16 edge trees and the 5, 5 and 9 in the middle row
+        assert_eq!(count_visible(&visible), 19);
+        assert!(visible[1][5]);
+        assert!(!visible[1][3]);
+    }
+
+    #[test]
+    fn test_tall_forest() {
+        let forest = Forest::parse("303\n255\n653\n335\n353\n129\n013").unwrap();
+        assert_eq!((forest.height(), forest.width()), (7, 3));
+        assert_eq!(visibility_map(&forest), ViewingRules::default().visibility_map(&forest));
+        assert_eq!(count_visible(&visibility_map(&forest)), 20);
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    // Arbitrary head move, `V dx dy` in the input
+    Vector(i32, i32),
+}
+
+impl Direction {
+    pub fn from_name(name: &str) -> Option<Direction> {
+        match name {
+            "U" => Some(Direction::Up),
+            "D" => Some(Direction::Down),
+            "L" => Some(Direction::Left),
+            "R" => Some(Direction::Right),
+            "UL" => Some(Direction::UpLeft),
+            "UR" => Some(Direction::UpRight),
+            "DL" => Some(Direction::DownLeft),
+            "DR" => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
+
+    // (dx, dy) of one move, y going up
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, 1),
+            Direction::UpRight => (1, 1),
+            Direction::DownLeft => (-1, -1),
+            Direction::DownRight => (1, -1),
+            Direction::Vector(dx, dy) => (*dx, *dy),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::Up => write!(f, "U"),
+            Direction::Down => write!(f, "D"),
+            Direction::Left => write!(f, "L"),
+            Direction::Right => write!(f, "R"),
+            Direction::UpLeft => write!(f, "UL"),
+            Direction::UpRight => write!(f, "UR"),
+            Direction::DownLeft => write!(f, "DL"),
+            Direction::DownRight => write!(f, "DR"),
+            Direction::Vector(dx, dy) => write!(f, "V {} {}", dx, dy),
+        }
+    }
+}
+
+// One line of input: the head moves `distance` times in `direction`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion {
+    pub direction: Direction,
+    pub distance: usize,
+}
+
+impl fmt::Display for Motion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.direction {
+            // A vector is always applied once
+            Direction::Vector(..) => write!(f, "{}", self.direction),
+            _ => write!(f, "{} {}", self.direction, self.distance),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    // Lines are 1-based
+    InvalidDirection { line: usize, direction: String },
+    InvalidNumber { line: usize, number: String },
+    MissingNumber { line: usize },
+    TrailingInput { line: usize, input: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidDirection { line, direction } =>
+                write!(f, "Invalid direction '{}' at line {}", direction, line),
+            ParseError::InvalidNumber { line, number } =>
+                write!(f, "Invalid number '{}' at line {}", number, line),
+            ParseError::MissingNumber { line } =>
+                write!(f, "Missing number at line {}", line),
+            ParseError::TrailingInput { line, input } =>
+                write!(f, "Unexpected '{}' at line {}", input, line),
+        }
+    }
+}
+
+// `U 4`, `DR 2` or `V -3 2`, one per line. Blank lines are skipped and any
+// whitespace separates the parts.
+pub fn parse_motions(input: &str) -> Result<Vec<Motion>, ParseError> {
+    let mut motions = vec![];
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        motions.push(parse_motion(line, i + 1)?);
+    }
+    Ok(motions)
+}
+
+fn parse_motion(text: &str, line: usize) -> Result<Motion, ParseError> {
+    let mut parts = text.split_whitespace();
+    let name = parts.next().unwrap_or("");
+    let motion = if name == "V" {
+        let dx = parse_number(parts.next(), line)?;
+        let dy = parse_number(parts.next(), line)?;
+        Motion { direction: Direction::Vector(dx, dy), distance: 1 }
+    } else {
+        let direction = Direction::from_name(name)
+            .ok_or(ParseError::InvalidDirection { line, direction: name.to_string() })?;
+        Motion { direction, distance: parse_number(parts.next(), line)? }
+    };
+    match parts.next() {
+        Some(input) => Err(ParseError::TrailingInput { line, input: input.to_string() }),
+        None => Ok(motion),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(part: Option<&str>, line: usize) -> Result<T, ParseError> {
+    let part = part.ok_or(ParseError::MissingNumber { line })?;
+    part.parse::<T>().map_err(|_| ParseError::InvalidNumber { line, number: part.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_motions() {
+        let motions = parse_motions("U 2\n\nDL\t13\n  R   100  \nV -3 12\n").unwrap();
+        assert_eq!(motions, vec![
+            Motion { direction: Direction::Up, distance: 2 },
+            Motion { direction: Direction::DownLeft, distance: 13 },
+            Motion { direction: Direction::Right, distance: 100 },
+            Motion { direction: Direction::Vector(-3, 12), distance: 1 },
+        ]);
+        assert_eq!(motions[1].to_string(), "DL 13");
+        assert_eq!(motions[3].to_string(), "V -3 12");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_motions("U 1\nX 2"), Err(ParseError::InvalidDirection { line: 2, direction: "X".to_string() }));
+        assert_eq!(parse_motions("\n\nR -1"), Err(ParseError::InvalidNumber { line: 3, number: "-1".to_string() }));
+        assert_eq!(parse_motions("L"), Err(ParseError::MissingNumber { line: 1 }));
+        assert_eq!(parse_motions("V 1"), Err(ParseError::MissingNumber { line: 1 }));
+        assert_eq!(parse_motions("D 4 2"), Err(ParseError::TrailingInput { line: 1, input: "2".to_string() }));
+        assert_eq!(parse_motions("U2").unwrap_err().to_string(), "Invalid direction 'U2' at line 1");
+    }
+}
@@ -0,0 +1,163 @@
+use std::{collections::HashSet, io::{self, Write}};
+
+use crate::{motion::Motion, rope::{Point, Rope}};
+
+// When `animate` draws the rope
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frames {
+    EachStep,
+    EachCommand,
+}
+
+impl Frames {
+    pub fn from_arg(arg: &str) -> Option<Frames> {
+        match arg {
+            "step" => Some(Frames::EachStep),
+            "command" => Some(Frames::EachCommand),
+            _ => None,
+        }
+    }
+}
+
+// Inclusive box of cells to draw
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    // Smallest box holding every cell any knot has visited
+    pub fn of(rope: &Rope) -> Bounds {
+        let mut bounds = Bounds { min: Point::default(), max: Point::default() };
+        for knot in 0..rope.knots().len() {
+            for point in rope.visited(knot) {
+                bounds.min = Point::new(bounds.min.x.min(point.x), bounds.min.y.min(point.y));
+                bounds.max = Point::new(bounds.max.x.max(point.x), bounds.max.y.max(point.y));
+            }
+        }
+        bounds
+    }
+
+    // Rows from the top, so y goes down while drawing
+    fn draw<F: Fn(Point) -> char>(&self, cell: F) -> String {
+        let mut output = String::new();
+        for y in (self.min.y..=self.max.y).rev() {
+            for x in self.min.x..=self.max.x {
+                output.push(cell(Point::new(x, y)));
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+// `H` for the head, then `1` to `9`, or `T` for the tail of a two knot rope
+fn label(knot: usize, length: usize) -> char {
+    match knot {
+        0 => 'H',
+        1 if length == 2 => 'T',
+        1..=9 => char::from_digit(knot as u32, 10).unwrap(),
+        _ => '*',
+    }
+}
+
+// Knots closer to the head are drawn over the ones behind them, `s` marks the start
+pub fn render_rope(rope: &Rope, bounds: &Bounds) -> String {
+    let knots = rope.knots();
+    bounds.draw(|point| {
+        match knots.iter().position(|knot| *knot == point) {
+            Some(knot) => label(knot, knots.len()),
+            None if point == Point::default() => 's',
+            None => '.',
+        }
+    })
+}
+
+pub fn render_visited(visited: &HashSet<Point>, bounds: &Bounds) -> String {
+    bounds.draw(|point| {
+        if point == Point::default() {
+            's'
+        } else if visited.contains(&point) {
+            '#'
+        } else {
+            '.'
+        }
+    })
+}
+
+// Writes the rope after every step or command like the puzzle statement, then the
+// cells visited by the tail. All frames share the bounds of the whole simulation.
+pub fn animate<W: Write>(motions: &[Motion], knots: usize, frames: Frames, output: &mut W) -> io::Result<Rope> {
+    let mut rope = Rope::new(knots);
+    for motion in motions {
+        rope.apply(motion);
+    }
+    let bounds = Bounds::of(&rope);
+
+    let mut rope = Rope::new(knots);
+    writeln!(output, "== Initial State ==\n\n{}", render_rope(&rope, &bounds))?;
+    for motion in motions {
+        writeln!(output, "== {} ==\n", motion)?;
+        for _ in 0..motion.distance {
+            rope.step(&motion.direction);
+            if frames == Frames::EachStep {
+                writeln!(output, "{}", render_rope(&rope, &bounds))?;
+            }
+        }
+        if frames == Frames::EachCommand {
+            writeln!(output, "{}", render_rope(&rope, &bounds))?;
+        }
+    }
+    writeln!(output, "== Tail positions ==\n\n{}", render_visited(rope.visited(knots - 1), &bounds))?;
+    Ok(rope)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motion::{parse_motions, Direction};
+
+    fn larger_example() -> Vec<Motion> {
+        parse_motions("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20\n").unwrap()
+    }
+
+    #[test]
+    fn test_render_rope() {
+        let mut rope = Rope::new(10);
+        for _ in 0..4 {
+            rope.step(&Direction::Right);
+        }
+        let bounds = Bounds { min: Point::new(0, 0), max: Point::new(5, 1) };
+        assert_eq!(render_rope(&rope, &bounds), "......\n4321H.\n");
+
+        let mut rope = Rope::new(2);
+        rope.step(&Direction::Up);
+        assert_eq!(render_rope(&rope, &Bounds { min: Point::new(0, 0), max: Point::new(1, 1) }), "H.\nT.\n");
+    }
+
+    #[test]
+    fn test_render_visited() {
+        let mut output = vec![];
+        let rope = animate(&larger_example(), 10, Frames::EachCommand, &mut output).unwrap();
+        let bounds = Bounds::of(&rope);
+        assert_eq!(bounds, Bounds { min: Point::new(-11, -5), max: Point::new(14, 15) });
+        let map = render_visited(rope.visited(9), &bounds);
+        assert_eq!(map.matches('#').count() + 1, 36);
+        // Same map as the puzzle statement
+        assert_eq!(map.lines().nth(15).unwrap(), "....#......s.........#....");
+        assert_eq!(map.lines().last().unwrap(), ".........########.........");
+    }
+
+    #[test]
+    fn test_animate() {
+        let mut output = vec![];
+        animate(&parse_motions("R 2\nU 1").unwrap(), 2, Frames::EachStep, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let expected = "== Initial State ==\n\n...\nH..\n\n\
+            == R 2 ==\n\n...\nTH.\n\n...\nsTH\n\n\
+            == U 1 ==\n\n..H\nsT.\n\n\
+            == Tail positions ==\n\n...\ns#.\n\n";
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+use crate::motion::{Direction, Motion};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    // Number of king moves between two points
+    pub fn chebyshev_distance(&self, other: &Point) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    // Touching, diagonally included, or overlapping
+    pub fn is_adjacent(&self, other: &Point) -> bool {
+        self.chebyshev_distance(other) <= 1
+    }
+
+    // Moves one step along each axis towards `target`
+    pub fn step_towards(&self, target: &Point) -> Point {
+        Point::new(self.x + (target.x - self.x).signum(), self.y + (target.y - self.y).signum())
+    }
+
+    pub fn moved(&self, direction: &Direction) -> Point {
+        let (dx, dy) = direction.offset();
+        Point::new(self.x + dx, self.y + dy)
+    }
+}
+
+pub struct Rope {
+    knots: Vec<Point>,
+    // Every position each knot has been at, the start included
+    visited: Vec<HashSet<Point>>,
+}
+
+impl Rope {
+    // A rope of `length` knots, all starting at the origin
+    pub fn new(length: usize) -> Rope {
+        assert!(length > 0, "A rope needs at least one knot");
+        Rope {
+            knots: vec![Point::default(); length],
+            visited: vec![HashSet::from([Point::default()]); length],
+        }
+    }
+
+    pub fn knots(&self) -> &[Point] {
+        &self.knots
+    }
+
+    // Moves the head one step and lets every other knot follow the one before it.
+    // Vectors longer than one cell are walked one king move at a time, so the
+    // knots never fall more than one cell behind.
+    pub fn step(&mut self, direction: &Direction) {
+        let target = self.knots[0].moved(direction);
+        while self.knots[0] != target {
+            self.knots[0] = self.knots[0].step_towards(&target);
+            self.follow();
+        }
+    }
+
+    pub fn apply(&mut self, motion: &Motion) {
+        for _ in 0..motion.distance {
+            self.step(&motion.direction);
+        }
+    }
+
+    fn follow(&mut self) {
+        for knot in 1..self.knots.len() {
+            let leader = self.knots[knot - 1];
+            if self.knots[knot].is_adjacent(&leader) {
+                // Knots further down can't move either
+                break;
+            }
+            self.knots[knot] = self.knots[knot].step_towards(&leader);
+            self.visited[knot].insert(self.knots[knot]);
+        }
+        self.visited[0].insert(self.knots[0]);
+    }
+
+    pub fn visited(&self, knot: usize) -> &HashSet<Point> {
+        &self.visited[knot]
+    }
+
+    // Number of distinct cells knot `knot` (0 being the head) has been at
+    pub fn visited_count(&self, knot: usize) -> usize {
+        self.visited[knot].len()
+    }
+
+    pub fn visited_counts(&self) -> Vec<usize> {
+        self.visited.iter().map(|positions| positions.len()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chebyshev_distance() {
+        let origin = Point::new(0, 0);
+        assert_eq!(origin.chebyshev_distance(&Point::new(1, 1)), 1);
+        assert_eq!(origin.chebyshev_distance(&Point::new(2, 1)), 2);
+        assert_eq!(origin.chebyshev_distance(&Point::new(-3, 0)), 3);
+        assert!(origin.is_adjacent(&Point::new(1, -1)));
+        assert!(!origin.is_adjacent(&Point::new(0, 2)));
+    }
+
+    #[test]
+    fn test_step_towards() {
+        let head = Point::new(0, 0);
+        assert_eq!(Point::new(2, 1).step_towards(&head), Point::new(1, 0));
+        assert_eq!(Point::new(0, 2).step_towards(&head), Point::new(0, 1));
+        assert_eq!(Point::new(-2, 0).step_towards(&head), Point::new(-1, 0));
+    }
+
+    #[test]
+    fn test_step() {
+        let mut rope = Rope::new(3);
+        rope.step(&Direction::Right);
+        assert_eq!(rope.knots(), &[Point::new(1, 0), Point::new(0, 0), Point::new(0, 0)]);
+        rope.step(&Direction::Right);
+        assert_eq!(rope.knots(), &[Point::new(2, 0), Point::new(1, 0), Point::new(0, 0)]);
+        rope.step(&Direction::Up);
+        rope.step(&Direction::Up);
+        assert_eq!(rope.knots()[0], Point::new(2, 2));
+        assert_eq!(rope.knots()[1], Point::new(2, 1));
+        assert_eq!(*rope.knots().last().unwrap(), Point::new(1, 1));
+    }
+
+    #[test]
+    fn test_diagonal_and_vector_steps() {
+        let mut rope = Rope::new(3);
+        rope.step(&Direction::UpRight);
+        rope.step(&Direction::UpRight);
+        assert_eq!(rope.knots(), &[Point::new(2, 2), Point::new(1, 1), Point::new(0, 0)]);
+
+        // Walked as two diagonal moves then one to the left
+        let mut rope = Rope::new(3);
+        rope.step(&Direction::Vector(-3, 2));
+        assert_eq!(rope.knots(), &[Point::new(-3, 2), Point::new(-2, 2), Point::new(-1, 1)]);
+        assert_eq!(rope.visited_counts(), vec![4, 3, 2]);
+        for pair in rope.knots().windows(2) {
+            assert!(pair[0].is_adjacent(&pair[1]));
+        }
+
+        // A zero vector leaves the rope where it is
+        rope.step(&Direction::Vector(0, 0));
+        assert_eq!(rope.knots()[0], Point::new(-3, 2));
+    }
+
+    #[test]
+    fn test_visited() {
+        let mut rope = Rope::new(3);
+        for direction in [Direction::Right, Direction::Right, Direction::Up, Direction::Up] {
+            rope.step(&direction);
+        }
+        assert_eq!(rope.visited_counts(), vec![5, 3, 2]);
+        assert!(rope.visited(2).contains(&Point::new(1, 1)));
+        // Going back over the same cells adds nothing
+        rope.step(&Direction::Down);
+        rope.step(&Direction::Up);
+        assert_eq!(rope.visited_counts(), vec![5, 3, 2]);
+    }
+}